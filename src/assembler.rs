@@ -0,0 +1,257 @@
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+
+pub const ROM_SIZE: usize = 32768;
+
+// first RAM address handed out to `@variable` symbols
+const VARIABLE_BASE: u16 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HackInstruction {
+    /// `@value`
+    A(u16),
+    /// `dest=comp;jump` with each part kept as its machine code bits
+    C { dest: u8, comp: u8, jump: u8 },
+}
+
+impl HackInstruction {
+    pub fn decode(word: u16) -> Self {
+        if word & 0x8000 == 0 {
+            Self::A(word)
+        } else {
+            Self::C {
+                dest: (word >> 3 & 0b111) as u8,
+                comp: (word >> 6 & 0b1111111) as u8,
+                jump: (word & 0b111) as u8,
+            }
+        }
+    }
+
+    pub fn encode(&self) -> u16 {
+        match *self {
+            Self::A(value) => value,
+            Self::C { dest, comp, jump } => {
+                0b111 << 13 | (comp as u16) << 6 | (dest as u16) << 3 | jump as u16
+            }
+        }
+    }
+}
+
+/// Hack assembly resolved down to instructions, plus every symbol it defined
+#[derive(Debug, Clone)]
+pub struct Program {
+    pub instructions: Vec<HackInstruction>,
+    pub symbols: HashMap<String, u16>,
+}
+
+impl Program {
+    /// Assembles Hack assembly text, in the same format `CodeWriter` emits
+    pub fn assemble(source: &str) -> Result<Self> {
+        let mut symbols = Self::predefined_symbols();
+
+        let length = source
+            .lines()
+            .map(Self::strip)
+            .filter(|line| !line.is_empty() && !line.starts_with('('))
+            .count();
+        if length > ROM_SIZE {
+            bail!(
+                "program needs {} instructions but ROM holds {}",
+                length,
+                ROM_SIZE
+            );
+        }
+
+        // first pass: labels refer to the address of the next instruction,
+        // which fits in a u16 now the program is known to fit in ROM
+        let mut address: u16 = 0;
+        for (index, line) in source.lines().enumerate() {
+            let line = Self::strip(line);
+            if let Some(label) = line.strip_prefix('(') {
+                let label = label.strip_suffix(')').with_context(|| {
+                    format!("line {}: unterminated label `{}`", index + 1, line)
+                })?;
+                if symbols.insert(label.to_owned(), address).is_some() {
+                    bail!("line {}: symbol `{}` is already defined", index + 1, label);
+                }
+            } else if !line.is_empty() {
+                address += 1;
+            }
+        }
+
+        // second pass: resolve instructions, allocating variables as they appear
+        let mut instructions = Vec::new();
+        let mut next_variable = VARIABLE_BASE;
+        for (index, line) in source.lines().enumerate() {
+            let line = Self::strip(line);
+            if line.is_empty() || line.starts_with('(') {
+                continue;
+            }
+
+            let instruction = if let Some(symbol) = line.strip_prefix('@') {
+                let value = match symbol.parse::<u16>() {
+                    Ok(value) if value <= i16::MAX as u16 => value,
+                    Ok(_) => bail!("line {}: constant `{}` is too large", index + 1, symbol),
+                    Err(_) => *symbols.entry(symbol.to_owned()).or_insert_with(|| {
+                        next_variable += 1;
+                        next_variable - 1
+                    }),
+                };
+                HackInstruction::A(value)
+            } else {
+                Self::parse_c_instruction(line).with_context(|| {
+                    format!("line {}: invalid instruction `{}`", index + 1, line)
+                })?
+            };
+
+            instructions.push(instruction);
+        }
+
+        Ok(Self {
+            instructions,
+            symbols,
+        })
+    }
+
+    /// Loads the contents of a .hack file, which carries no symbols
+    pub fn from_hack(source: &str) -> Result<Self> {
+        let mut instructions = Vec::new();
+
+        for (index, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line.len() != 16 {
+                bail!(
+                    "line {}: expected 16 binary digits, found `{}`",
+                    index + 1,
+                    line
+                );
+            }
+
+            let word = u16::from_str_radix(line, 2)
+                .with_context(|| format!("line {}: invalid binary word `{}`", index + 1, line))?;
+            instructions.push(HackInstruction::decode(word));
+        }
+
+        Ok(Self {
+            instructions,
+            symbols: HashMap::new(),
+        })
+    }
+
+    /// Machine code for every instruction, one 16-bit word each
+    pub fn encode(&self) -> Vec<u16> {
+        self.instructions
+            .iter()
+            .map(|instruction| instruction.encode())
+            .collect()
+    }
+
+    /// The contents of a .hack file: one word per line as binary digits
+    pub fn to_hack(&self) -> String {
+        self.encode()
+            .iter()
+            .map(|word| format!("{:016b}\n", word))
+            .collect()
+    }
+
+    fn strip(line: &str) -> &str {
+        line.split("//").next().unwrap().trim()
+    }
+
+    fn predefined_symbols() -> HashMap<String, u16> {
+        let mut symbols = HashMap::from([
+            ("SP".to_owned(), 0),
+            ("LCL".to_owned(), 1),
+            ("ARG".to_owned(), 2),
+            ("THIS".to_owned(), 3),
+            ("THAT".to_owned(), 4),
+            ("SCREEN".to_owned(), 16384),
+            ("KBD".to_owned(), 24576),
+        ]);
+
+        for register in 0..16 {
+            symbols.insert(format!("R{}", register), register);
+        }
+
+        symbols
+    }
+
+    fn parse_c_instruction(line: &str) -> Option<HackInstruction> {
+        let (dest, rest) = match line.split_once('=') {
+            Some((dest, rest)) => (dest.trim(), rest),
+            None => ("", line),
+        };
+        let (comp, jump) = match rest.split_once(';') {
+            Some((comp, jump)) => (comp.trim(), jump.trim()),
+            None => (rest.trim(), ""),
+        };
+
+        let mut dest_bits = 0;
+        for register in dest.chars() {
+            let bit = match register {
+                'A' => 0b100,
+                'D' => 0b010,
+                'M' => 0b001,
+                _ => return None,
+            };
+            if dest_bits & bit != 0 {
+                return None;
+            }
+            dest_bits |= bit;
+        }
+
+        let jump_bits = match jump {
+            "" => 0b000,
+            "JGT" => 0b001,
+            "JEQ" => 0b010,
+            "JGE" => 0b011,
+            "JLT" => 0b100,
+            "JNE" => 0b101,
+            "JLE" => 0b110,
+            "JMP" => 0b111,
+            _ => return None,
+        };
+
+        Some(HackInstruction::C {
+            dest: dest_bits,
+            comp: Self::comp_bits(comp)?,
+            jump: jump_bits,
+        })
+    }
+
+    /// The `a c1..c6` bits of a computation, where `a` selects M over A
+    fn comp_bits(comp: &str) -> Option<u8> {
+        let (a, comp) = if comp.contains('M') {
+            (0b1000000, comp.replace('M', "A"))
+        } else {
+            (0, comp.to_owned())
+        };
+
+        let bits = match comp.as_str() {
+            "0" => 0b101010,
+            "1" => 0b111111,
+            "-1" => 0b111010,
+            "D" => 0b001100,
+            "A" => 0b110000,
+            "!D" => 0b001101,
+            "!A" => 0b110001,
+            "-D" => 0b001111,
+            "-A" => 0b110011,
+            "D+1" => 0b011111,
+            "A+1" => 0b110111,
+            "D-1" => 0b001110,
+            "A-1" => 0b110010,
+            "D+A" | "A+D" => 0b000010,
+            "D-A" => 0b010011,
+            "A-D" => 0b000111,
+            "D&A" | "A&D" => 0b000000,
+            "D|A" | "A|D" => 0b010101,
+            _ => return None,
+        };
+
+        Some(a | bits)
+    }
+}
@@ -0,0 +1,36 @@
+/// Configuration for the code placed at the very start of the program that
+/// sets up the VM before handing control to the entry function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bootstrap {
+    /// initial value of SP
+    pub stack_base: u16,
+    /// initial values of LCL, ARG, THIS and THAT, left untouched when `None`
+    pub segment_pointers: Option<[i16; 4]>,
+    /// function called once the registers are set up
+    pub entry: String,
+}
+
+impl Bootstrap {
+    /// The values the course's test scripts put in LCL, ARG, THIS and THAT,
+    /// so reads through an uninitialised segment are easy to spot.
+    pub const SENTINELS: [i16; 4] = [-1, -2, -3, -4];
+
+    pub fn new(stack_base: u16, entry: &str) -> Self {
+        Self {
+            stack_base,
+            segment_pointers: None,
+            entry: entry.to_owned(),
+        }
+    }
+
+    pub fn with_sentinels(mut self) -> Self {
+        self.segment_pointers = Some(Self::SENTINELS);
+        self
+    }
+}
+
+impl Default for Bootstrap {
+    fn default() -> Self {
+        Self::new(256, "Sys.init")
+    }
+}
@@ -0,0 +1,271 @@
+use crate::op_code::{Instruction, OpCode};
+use std::collections::{HashMap, HashSet};
+
+/// A `call` made from inside a function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallSite {
+    pub callee: String,
+    pub num_args: u8,
+    pub line: usize,
+}
+
+/// A function declared with `function`, along with every call it makes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionNode {
+    pub name: String,
+    /// path of the file it is declared in
+    pub file: String,
+    /// static namespace of that file
+    pub namespace: String,
+    pub line: usize,
+    pub num_locals: u8,
+    pub calls: Vec<CallSite>,
+}
+
+/// Which functions call which, across every file of a program
+#[derive(Debug, Clone, Default)]
+pub struct CallGraph {
+    /// in program order
+    functions: Vec<FunctionNode>,
+    /// calls made by code before the first function of a file, which runs
+    /// when the program doesn't start from a bootstrap call
+    top_level_calls: Vec<CallSite>,
+    index: HashMap<String, usize>,
+}
+
+impl CallGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_file(&mut self, filename: &str, namespace: &str, instructions: &[Instruction]) {
+        let mut current = None;
+
+        for instruction in instructions {
+            match &instruction.op_code {
+                OpCode::Function {
+                    func_name,
+                    num_locals,
+                } => {
+                    self.functions.push(FunctionNode {
+                        name: func_name.to_string(),
+                        file: filename.to_owned(),
+                        namespace: namespace.to_owned(),
+                        line: instruction.line,
+                        num_locals: *num_locals,
+                        calls: Vec::new(),
+                    });
+                    // the first definition wins if a name is reused
+                    let index = self.functions.len() - 1;
+                    self.index.entry(func_name.to_string()).or_insert(index);
+                    current = Some(index);
+                }
+                OpCode::Call {
+                    func_name,
+                    num_args,
+                } => {
+                    let call = CallSite {
+                        callee: func_name.to_string(),
+                        num_args: *num_args,
+                        line: instruction.line,
+                    };
+                    match current {
+                        Some(index) => self.functions[index].calls.push(call),
+                        None => self.top_level_calls.push(call),
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub fn functions(&self) -> &[FunctionNode] {
+        &self.functions
+    }
+
+    pub fn function(&self, name: &str) -> Option<&FunctionNode> {
+        self.index.get(name).map(|&index| &self.functions[index])
+    }
+
+    pub fn top_level_calls(&self) -> &[CallSite] {
+        &self.top_level_calls
+    }
+
+    /// Functions that are called but not defined anywhere, such as the OS
+    /// when it isn't part of the program, in order of first call
+    pub fn external_functions(&self) -> Vec<&str> {
+        let mut external: Vec<&str> = Vec::new();
+        let calls = self
+            .functions
+            .iter()
+            .flat_map(|function| &function.calls)
+            .chain(&self.top_level_calls);
+
+        for call in calls {
+            if self.function(&call.callee).is_none() && !external.contains(&call.callee.as_str()) {
+                external.push(&call.callee);
+            }
+        }
+
+        external
+    }
+
+    /// Every function that can be reached from `roots` or from top-level
+    /// code by following calls. Calls to undefined functions are ignored.
+    pub fn reachable(&self, roots: &[&str]) -> HashSet<String> {
+        let mut reachable = HashSet::new();
+        let mut pending: Vec<&str> = roots.to_vec();
+        pending.extend(self.top_level_calls.iter().map(|call| call.callee.as_str()));
+
+        while let Some(name) = pending.pop() {
+            let Some(function) = self.function(name) else {
+                continue;
+            };
+            if reachable.insert(function.name.clone()) {
+                pending.extend(function.calls.iter().map(|call| call.callee.as_str()));
+            }
+        }
+
+        reachable
+    }
+}
+
+/// Output formats of `CallGraph::export`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum GraphFormat {
+    /// Graphviz DOT
+    #[default]
+    Dot,
+    Json,
+}
+
+/// The calls from one function to another with the same argument count
+struct Edge<'a> {
+    callee: &'a str,
+    num_args: u8,
+    count: usize,
+}
+
+impl CallGraph {
+    pub fn export(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::Json => self.to_json(),
+        }
+    }
+
+    /// Calls grouped by callee and argument count, in order of first call
+    fn edges(calls: &[CallSite]) -> Vec<Edge<'_>> {
+        let mut edges: Vec<Edge> = Vec::new();
+
+        for call in calls {
+            let existing = edges
+                .iter_mut()
+                .find(|edge| edge.callee == call.callee && edge.num_args == call.num_args);
+            match existing {
+                Some(edge) => edge.count += 1,
+                None => edges.push(Edge {
+                    callee: &call.callee,
+                    num_args: call.num_args,
+                    count: 1,
+                }),
+            }
+        }
+
+        edges
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph calls {\n");
+
+        for function in &self.functions {
+            dot.push_str(&format!(
+                "  \"{}\" [label=\"{}\\n{}:{}, {} local(s)\"];\n",
+                escape(&function.name),
+                escape(&function.name),
+                escape(&function.file),
+                function.line,
+                function.num_locals
+            ));
+        }
+        for name in self.external_functions() {
+            dot.push_str(&format!(
+                "  \"{}\" [label=\"{}\\n(external)\", style=dashed];\n",
+                escape(name),
+                escape(name)
+            ));
+        }
+
+        let callers = self
+            .functions
+            .iter()
+            .map(|function| (function.name.as_str(), &function.calls));
+        let top_level =
+            (!self.top_level_calls.is_empty()).then_some(("<top level>", &self.top_level_calls));
+
+        for (caller, calls) in callers.chain(top_level) {
+            for edge in Self::edges(calls) {
+                dot.push_str(&format!(
+                    "  \"{}\" -> \"{}\" [label=\"{} arg(s) x{}\"];\n",
+                    escape(caller),
+                    escape(edge.callee),
+                    edge.num_args,
+                    edge.count
+                ));
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub fn to_json(&self) -> String {
+        let calls_json = |calls: &[CallSite]| {
+            let edges: Vec<String> = Self::edges(calls)
+                .iter()
+                .map(|edge| {
+                    format!(
+                        "{{\"callee\": \"{}\", \"num_args\": {}, \"count\": {}}}",
+                        escape(edge.callee),
+                        edge.num_args,
+                        edge.count
+                    )
+                })
+                .collect();
+            format!("[{}]", edges.join(", "))
+        };
+
+        let functions: Vec<String> = self
+            .functions
+            .iter()
+            .map(|function| {
+                format!(
+                    "    {{\"name\": \"{}\", \"file\": \"{}\", \"line\": {}, \"num_locals\": {}, \"calls\": {}}}",
+                    escape(&function.name),
+                    escape(&function.file),
+                    function.line,
+                    function.num_locals,
+                    calls_json(&function.calls)
+                )
+            })
+            .collect();
+
+        let external: Vec<String> = self
+            .external_functions()
+            .iter()
+            .map(|name| format!("\"{}\"", escape(name)))
+            .collect();
+
+        format!(
+            "{{\n  \"functions\": [\n{}\n  ],\n  \"top_level_calls\": {},\n  \"external_functions\": [{}]\n}}\n",
+            functions.join(",\n"),
+            calls_json(&self.top_level_calls),
+            external.join(", ")
+        )
+    }
+}
+
+/// Escapes a name for a double-quoted DOT or JSON string
+fn escape(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -1,7 +1,10 @@
-use crate::op_code::{LabelOpCode, OpCode, SegmentOpCode};
-use anyhow::{Ok, Result};
-use rand::{distributions::Alphanumeric, Rng};
-use std::io::Write;
+use crate::{
+    bootstrap::Bootstrap,
+    op_code::{LabelOpCode, OpCode, Segment, SegmentOpCode},
+    peephole::{self, Line},
+};
+use anyhow::{bail, Ok, Result};
+use std::{collections::HashMap, io::Write};
 
 // const SP: u8 = 0; // stores the memory address of the topmost stack value
 // const LCL: u8 = 1; // stores the base address of the local virtual segment
@@ -12,6 +15,37 @@ use std::io::Write;
 // const STACK: u16 = 256;
 // static variables are going to be XXX.i where XXX is the name of the Generated file
 
+/// The only registers generated code may use for its own bookkeeping. Every
+/// role is one of R13-R15, so the temp segment in R5-R12 belongs entirely to
+/// the VM program and survives calls and returns.
+pub(crate) mod scratch {
+    /// target address of a `pop`
+    pub const POP_ADDRESS: &str = "@R13";
+    /// function the shared call routine jumps to
+    pub const CALL_TARGET: &str = "@R13";
+    /// argument count passed to the shared call routine
+    pub const CALL_ARGS: &str = "@R14";
+    /// base of the frame being returned from
+    pub const RETURN_FRAME: &str = "@R14";
+    /// where a return jumps back to
+    pub const RETURN_ADDRESS: &str = "@R15";
+    /// a pushed value held by the peephole optimizer while it works out the
+    /// address of the pop it was fused into
+    pub const FUSED_VALUE: &str = "@R14";
+    /// the right operand of `gt` and `lt` while the operands' signs are checked
+    pub const COMPARE_OPERAND: &str = "@R13";
+}
+
+/// Where a segment's values live in RAM
+enum SegmentAddress {
+    /// relative to the base address held in a pointer register
+    Based(&'static str),
+    /// a fixed RAM address or static symbol
+    Direct(String),
+    /// not in RAM at all, the index is the value
+    Constant,
+}
+
 /**
  * 0-15 virtual registers
  * 16-255 static variables
@@ -30,71 +64,151 @@ use std::io::Write;
  * 7. temp
  * 8. constant
  */
-
-struct FunctionNameStack(Vec<String>);
-
-impl FunctionNameStack {
-    fn new() -> Self {
-        Self(Vec::new())
-    }
-
-    fn push(&mut self, func_name: &str) {
-        self.0.push(func_name.to_owned())
-    }
-
-    fn pop(&mut self) {
-        self.0.pop();
-    }
-
-    fn peek(&self) -> Option<String> {
-        self.0.last().map(|i| i.to_owned())
-    }
-}
-
 pub struct CodeWriter<'a> {
     writer: &'a mut dyn Write,
+    /// output not yet passed through the optimizer and written out
+    lines: Vec<Line>,
     lines_written: u32,
     current_filename: String,
-    function_name: FunctionNameStack,
+    /// the function of the most recent `function` op code in this file
+    current_function: Option<String>,
+    label_counters: HashMap<(String, &'static str), u32>,
+    /// jump to the global `$$CALL` and `$$RETURN` routines instead of inlining
+    shared_routines: bool,
+    /// whether any code jumped to the shared routines, which are then
+    /// written on `flush`
+    routines_used: bool,
+    /// peephole optimisation level applied on `flush`
+    opt_level: u8,
 }
 
 impl<'a> CodeWriter<'a> {
-    pub fn new(writer: &'a mut dyn Write, bootstrap: bool) -> Self {
+    pub fn new(writer: &'a mut dyn Write, bootstrap: Option<&Bootstrap>) -> Self {
         let mut code_writer = Self {
             writer,
+            lines: Vec::new(),
             lines_written: 0,
             current_filename: "Sys".to_owned(),
-            function_name: FunctionNameStack::new(),
+            current_function: None,
+            label_counters: HashMap::new(),
+            shared_routines: false,
+            routines_used: false,
+            opt_level: 0,
         };
 
         // VM Initialization
-        if bootstrap {
-            code_writer.write_init();
+        if let Some(bootstrap) = bootstrap {
+            code_writer.write_init(bootstrap);
         }
 
         code_writer
     }
 
-    fn write_init(&mut self) {
+    /// Makes every `call` and `return` after this point jump to one shared
+    /// copy of the frame handling code, trading a few cycles per call for a
+    /// much smaller program. The bootstrap call is still inlined.
+    pub fn with_shared_routines(mut self) -> Self {
+        self.shared_routines = true;
+        self
+    }
+
+    /// Runs the peephole optimizer over the output before writing it out,
+    /// see `peephole::optimize` for what each level does
+    pub fn with_opt_level(mut self, opt_level: u8) -> Self {
+        self.opt_level = opt_level;
+        self
+    }
+
+    fn write_init(&mut self, bootstrap: &Bootstrap) {
         // bootstrap code
         // this must be placed at the beginning of the output file
-        self.write("@256");
+        self.comment("bootstrap");
+
+        // SP = stack base
+        self.write(&format!("@{}", bootstrap.stack_base));
         self.write("D=A");
         self.write("@SP");
-        self.write_call("Sys.init", 0)
+        self.write("M=D");
+
+        if let Some(pointers) = bootstrap.segment_pointers {
+            for (register, value) in ["@LCL", "@ARG", "@THIS", "@THAT"].iter().zip(pointers) {
+                self.write_constant(value);
+                self.write(register);
+                self.write("M=D");
+            }
+        }
+
+        self.write_call(&bootstrap.entry, 0)
+    }
+
+    /// Loads any 16-bit value into the D Register
+    fn write_constant(&mut self, value: i16) {
+        match value {
+            -1..=1 => self.write(&format!("D={}", value)),
+            i16::MIN => {
+                self.write(&format!("@{}", i16::MAX));
+                self.write("D=!A");
+            }
+            _ if value < 0 => {
+                self.write(&format!("@{}", -value));
+                self.write("D=-A");
+            }
+            _ => {
+                self.write(&format!("@{}", value));
+                self.write("D=A");
+            }
+        }
+    }
+
+    /// Number of instructions written so far, which is also the ROM address
+    /// the next instruction will be loaded at
+    pub fn instruction_count(&self) -> u32 {
+        self.lines_written
     }
 
     pub fn set_current_filename(&mut self, filename: &str) {
-        self.current_filename = filename.to_owned()
+        self.current_filename = filename.to_owned();
+        self.current_function = None;
     }
 
+    /// Labels are scoped to this, the file name for code before any function
     fn get_current_func(&self) -> String {
-        let func_name = self.function_name.peek();
-        if func_name.is_some() {
-            func_name.unwrap()
-        } else {
-            "Sys".to_owned()
-        }
+        self.current_function
+            .clone()
+            .unwrap_or_else(|| self.current_filename.clone())
+    }
+
+    /// Writes the assembly for any op code, preceded by a comment naming it
+    pub fn write_op_code(&mut self, op_code: &OpCode) -> Result<()> {
+        self.comment(&op_code.to_string());
+
+        match op_code {
+            OpCode::Add
+            | OpCode::Sub
+            | OpCode::Neg
+            | OpCode::Eq
+            | OpCode::Gt
+            | OpCode::Lt
+            | OpCode::And
+            | OpCode::Or
+            | OpCode::Not => self.write_arithmetic(op_code),
+            OpCode::Push(push_op_code) => self.write_push(push_op_code),
+            OpCode::Pop(pop_op_code) => return self.write_pop(pop_op_code),
+            OpCode::Label(op_code) => self.write_label(op_code.label),
+            OpCode::Goto(op_code) => self.write_goto(op_code),
+            OpCode::If(op_code) => self.write_if(op_code),
+            OpCode::Call {
+                func_name,
+                num_args,
+            } => self.write_call(func_name, *num_args),
+            OpCode::Function {
+                func_name,
+                num_locals,
+            } => self.write_function(func_name, *num_locals),
+            OpCode::Return => self.write_return(),
+        };
+
+        Ok(())
     }
 
     fn write_to_stack(&mut self) {
@@ -122,9 +236,7 @@ impl<'a> CodeWriter<'a> {
                 self.pop_stack();
                 self.write("D=-D")
             }
-            OpCode::Eq => self.write_conditional("D;JEQ"),
-            OpCode::Gt => self.write_conditional("D;JGT"),
-            OpCode::Lt => self.write_conditional("D;JLT"),
+            OpCode::Eq | OpCode::Gt | OpCode::Lt => self.write_conditional(op_code),
             OpCode::And => {
                 self.write_double_operand();
                 self.write("D=D&M")
@@ -150,66 +262,71 @@ impl<'a> CodeWriter<'a> {
         self.write("M=M+1");
     }
 
+    fn segment_address(&self, op_code: &SegmentOpCode) -> SegmentAddress {
+        let segment = op_code.segment;
+        if segment.is_scoped_segment() {
+            let base = match segment {
+                Segment::Local => "@LCL",
+                Segment::Argument => "@ARG",
+                Segment::This => "@THIS",
+                _ => "@THAT",
+            };
+            return SegmentAddress::Based(base);
+        }
+
+        match segment {
+            Segment::Temp => SegmentAddress::Direct(format!("@{}", 5 + op_code.offset)),
+            Segment::Pointer => SegmentAddress::Direct(format!("@{}", 3 + op_code.offset)),
+            Segment::Static => {
+                SegmentAddress::Direct(format!("@{}.{}", self.current_filename, op_code.offset))
+            }
+            _ => SegmentAddress::Constant,
+        }
+    }
+
     pub fn write_push(&mut self, op_code: &SegmentOpCode) {
         // retrieve value from segment and store in D Register
-        if op_code.is_scoped_segment() {
-            match op_code.segment {
-                "local" => self.write("@LCL"),
-                "argument" => self.write("@ARG"),
-                "this" => self.write("@THIS"),
-                "that" => self.write("@THAT"),
-                _ => {}
+        match self.segment_address(op_code) {
+            SegmentAddress::Based(base) => {
+                self.write(base);
+                self.write("D=M");
+                self.write(&format!("@{}", op_code.offset));
+                self.write("A=D+A");
+                self.write("D=M");
             }
-
-            self.write("D=M");
-            self.write(&format!("@{}", op_code.offset));
-            self.write("A=D+A");
-            self.write("D=M");
-        } else if op_code.segment == "constant" {
-            self.write(&format!("@{}", op_code.offset));
-            self.write("D=A");
-        } else {
-            match op_code.segment {
-                "temp" => self.write(&format!("@{}", 5 + op_code.offset)),
-                "pointer" => self.write(&format!("@{}", 3 + op_code.offset)),
-                "static" => self.write(&format!("@{}.{}", self.current_filename, op_code.offset)),
-                _ => {}
+            SegmentAddress::Direct(address) => {
+                self.write(&address);
+                self.write("D=M");
+            }
+            SegmentAddress::Constant => {
+                self.write(&format!("@{}", op_code.offset));
+                self.write("D=A");
             }
-
-            self.write("D=M");
         }
 
         self.write_to_stack()
     }
 
-    pub fn write_pop(&mut self, op_code: &SegmentOpCode) {
-        if op_code.is_scoped_segment() {
-            // set segment
-            match op_code.segment {
-                "local" => self.write("@LCL"),
-                "argument" => self.write("@ARG"),
-                "this" => self.write("@THIS"),
-                "that" => self.write("@THAT"),
-                _ => {}
+    /// Fails on a pop to the constant segment, which has nowhere to store
+    /// the value
+    pub fn write_pop(&mut self, op_code: &SegmentOpCode) -> Result<()> {
+        match self.segment_address(op_code) {
+            SegmentAddress::Based(base) => {
+                // get address
+                self.write(base);
+                self.write("D=M");
+                self.write(&format!("@{}", op_code.offset));
+                self.write("D=D+A");
             }
-
-            // get address
-            self.write("D=M");
-            self.write(&format!("@{}", op_code.offset));
-            self.write("D=D+A");
-        } else {
-            match op_code.segment {
-                "temp" => self.write(&format!("@{}", 5 + op_code.offset)),
-                "pointer" => self.write(&format!("@{}", 3 + op_code.offset)),
-                "static" => self.write(&format!("@{}.{}", self.current_filename, op_code.offset)),
-                _ => {}
+            SegmentAddress::Direct(address) => {
+                self.write(&address);
+                self.write("D=A");
             }
-
-            self.write("D=A");
+            SegmentAddress::Constant => bail!("cannot pop to the `constant` segment"),
         }
 
-        // store address in @R13
-        self.write("@R13");
+        // store address in R13
+        self.write(scratch::POP_ADDRESS);
         self.write("M=D");
 
         // pop_stack value
@@ -219,19 +336,21 @@ impl<'a> CodeWriter<'a> {
         self.write("M=0");
 
         // update address to popped value
-        self.write("@R13");
+        self.write(scratch::POP_ADDRESS);
         self.write("A=M");
         self.write("M=D");
+
+        Ok(())
     }
 
     pub fn write_label(&mut self, label: &str) {
         let func_name = self.get_current_func();
-        self.label(&format!("({}__{})", func_name, label))
+        self.label(&format!("({}${})", func_name, label))
     }
 
     pub fn write_goto(&mut self, op_code: &LabelOpCode) {
         let func_name = self.get_current_func();
-        self.write(&format!("@{}__{}", func_name, op_code.label));
+        self.write(&format!("@{}${}", func_name, op_code.label));
         self.write("0;JMP")
     }
 
@@ -240,43 +359,61 @@ impl<'a> CodeWriter<'a> {
         self.pop_stack();
 
         let func_name = self.get_current_func();
-        self.write(&format!("@{}__{}", func_name, op_code.label));
+        self.write(&format!("@{}${}", func_name, op_code.label));
         self.write("D;JNE")
     }
 
     pub fn write_call(&mut self, func_name: &str, num_args: u8) {
-        // push return-address
-        let fn_name = self.get_current_func();
-        let return_address = format!("$ret__{}", Self::gen_return_string());
-        self.write(&format!("@{}__{}", fn_name, return_address));
-        self.write("D=A");
-        self.write_to_stack();
+        let return_address = self.unique_label("ret");
 
-        // push LCL
-        self.write("@LCL");
-        self.write("D=M");
-        self.write_to_stack();
+        if self.shared_routines {
+            // R13 = f, R14 = n, D = return-address
+            self.write(&format!("@{}", func_name));
+            self.write("D=A");
+            self.write(scratch::CALL_TARGET);
+            self.write("M=D");
+            self.write(&format!("@{}", num_args));
+            self.write("D=A");
+            self.write(scratch::CALL_ARGS);
+            self.write("M=D");
+            self.write(&format!("@{}", return_address));
+            self.write("D=A");
 
-        // push ARG
-        self.write("@ARG");
-        self.write("D=M");
-        self.write_to_stack();
+            self.write("@$$CALL");
+            self.write("0;JMP");
+            self.routines_used = true;
+        } else {
+            // push return-address
+            self.write(&format!("@{}", return_address));
+            self.write("D=A");
+            self.write_to_stack();
 
-        // push THIS
-        self.write("@THIS");
-        self.write("D=M");
-        self.write_to_stack();
+            // ARG = SP-n-5
+            self.write_call_frame(&format!("@{}", num_args), "D=D-A");
 
-        // push THAT
-        self.write("@THAT");
-        self.write("D=M");
-        self.write_to_stack();
+            // goto f
+            self.write(&format!("@{}", func_name));
+            self.write("0;JMP");
+        }
+
+        // (return-address)
+        self.label(&format!("({})", return_address));
+    }
+
+    /// Pushes LCL, ARG, THIS and THAT, then repositions ARG and LCL for the
+    /// callee, reading the argument count with `num_args` and `subtract`
+    fn write_call_frame(&mut self, num_args: &str, subtract: &str) {
+        for register in ["@LCL", "@ARG", "@THIS", "@THAT"] {
+            self.write(register);
+            self.write("D=M");
+            self.write_to_stack();
+        }
 
         // ARG = SP-n-5
         self.write("@SP");
         self.write("D=M");
-        self.write(&format!("@{}", num_args));
-        self.write("D=D-A");
+        self.write(num_args);
+        self.write(subtract);
         self.write("@5");
         self.write("D=D-A");
         self.write("@ARG");
@@ -287,30 +424,36 @@ impl<'a> CodeWriter<'a> {
         self.write("D=M");
         self.write("@LCL");
         self.write("M=D");
-
-        // goto f
-        self.write(&format!("@{}", func_name));
-        self.write("0;JMP");
-
-        // (return-address)
-        self.write_label(&return_address);
     }
 
     pub fn write_return(&mut self) {
+        if self.shared_routines {
+            self.write("@$$RETURN");
+            self.write("0;JMP");
+            self.routines_used = true;
+        } else {
+            self.write_frame_return();
+        }
+    }
+
+    /// Restores the caller's frame and jumps back to it
+    fn write_frame_return(&mut self) {
+        let (frame, ret) = (scratch::RETURN_FRAME, scratch::RETURN_ADDRESS);
+
         // FRAME = LCL
         self.write("@LCL");
         self.write("D=M");
-        self.write("@R6");
+        self.write(frame);
         self.write("M=D");
 
         // RET = *(FRAME-5)
-        self.write("@R6");
+        self.write(frame);
         self.write("D=M");
         self.write("@5");
         self.write("D=D-A");
         self.write("A=D");
         self.write("D=M");
-        self.write("@R7");
+        self.write(ret);
         self.write("M=D");
 
         // *ARG = pop_stack()
@@ -325,95 +468,92 @@ impl<'a> CodeWriter<'a> {
         self.write("@SP");
         self.write("M=D");
 
-        // THAT = *(FRAME-1)
-        self.write("@R6");
-        self.write("D=M");
-        self.write("@1");
-        self.write("D=D-A");
-        self.write("A=D");
-        self.write("D=M");
-        self.write("@THAT");
-        self.write("M=D");
-
-        // THIS = *(FRAME-2)
-        self.write("@R6");
-        self.write("D=M");
-        self.write("@2");
-        self.write("D=D-A");
-        self.write("A=D");
-        self.write("D=M");
-        self.write("@THIS");
-        self.write("M=D");
+        // THAT = *(FRAME-1), THIS = *(FRAME-2), ARG = *(FRAME-3), LCL = *(FRAME-4)
+        for (offset, register) in ["@THAT", "@THIS", "@ARG", "@LCL"].iter().enumerate() {
+            self.write(frame);
+            self.write("D=M");
+            self.write(&format!("@{}", offset + 1));
+            self.write("D=D-A");
+            self.write("A=D");
+            self.write("D=M");
+            self.write(register);
+            self.write("M=D");
+        }
 
-        // ARG = *(FRAME-3)
-        self.write("@R6");
-        self.write("D=M");
-        self.write("@3");
-        self.write("D=D-A");
-        self.write("A=D");
-        self.write("D=M");
-        self.write("@ARG");
-        self.write("M=D");
+        // GOTO RET
+        self.write(ret);
+        self.write("A=M");
+        self.write("0;JMP");
+    }
 
-        // LCL = *(FRAME-4)
-        self.write("@R6");
-        self.write("D=M");
-        self.write("@4");
-        self.write("D=D-A");
-        self.write("A=D");
-        self.write("D=M");
-        self.write("@LCL");
-        self.write("M=D");
+    /// The `$$CALL` and `$$RETURN` routines, behind a halt loop so execution
+    /// can't fall into them from the end of the program
+    fn write_shared_routines(&mut self) {
+        self.comment("end of program");
+        self.label("($$END)");
+        self.write("@$$END");
+        self.write("0;JMP");
 
-        // GOTO RET
-        self.write("@R7");
+        self.comment("shared call routine: R13 = function, R14 = nArgs, D = return address");
+        self.label("($$CALL)");
+        self.write_to_stack();
+        self.write_call_frame(scratch::CALL_ARGS, "D=D-M");
+        self.write(scratch::CALL_TARGET);
         self.write("A=M");
         self.write("0;JMP");
 
-        self.function_name.pop();
+        self.comment("shared return routine");
+        self.label("($$RETURN)");
+        self.write_frame_return();
     }
 
     pub fn write_function(&mut self, func_name: &str, num_locals: u8) {
-        self.function_name.push(&func_name);
+        self.current_function = Some(func_name.to_owned());
         self.label(&format!("({})", func_name));
 
-        for k in 0..num_locals {
-            self.write("@LCL");
-            self.write("D=M");
-            self.write(&format!("@{}", k));
-            self.write("D=D+A");
-
-            // store address in @R13
-            self.write("@R13");
-            self.write("M=D");
-
-            // pop_stack value
-            self.write("@0");
-            self.write("D=A");
-
-            // update address to popped value
-            self.write("@R13");
-            self.write("A=M");
-            self.write("M=D");
+        // push 0 for every local
+        for _ in 0..num_locals {
+            self.write("D=0");
+            self.write_to_stack();
         }
     }
 
     pub fn comment(&mut self, comment: &str) {
-        write!(&mut self.writer, "// {}\n", comment).unwrap();
+        self.lines.push(Line::Comment(comment.to_owned()));
     }
 
+    /// Writes out the shared routines if anything used them, which makes
+    /// this the last thing to call on a writer
     pub fn flush(&mut self) -> Result<()> {
+        if self.routines_used {
+            self.routines_used = false;
+            self.write_shared_routines();
+        }
+
+        let count = |lines: &[Line]| lines.iter().filter(|line| line.is_instruction()).count();
+        let before = count(&self.lines);
+        peephole::optimize(&mut self.lines, self.opt_level);
+        self.lines_written -= (before - count(&self.lines)) as u32;
+
+        for line in self.lines.drain(..) {
+            match line {
+                Line::Instruction(instruction) => writeln!(self.writer, "\t{}", instruction)?,
+                Line::Label(label) => writeln!(self.writer, "{}", label)?,
+                Line::Comment(comment) => writeln!(self.writer, "// {}", comment)?,
+            }
+        }
+
         self.writer.flush()?;
         Ok(())
     }
 
     fn write(&mut self, line: &str) {
-        write!(&mut self.writer, "\t{}\n", line).unwrap();
+        self.lines.push(Line::Instruction(line.to_owned()));
         self.lines_written += 1;
     }
 
     fn label(&mut self, line: &str) {
-        write!(&mut self.writer, "{}\n", line).unwrap();
+        self.lines.push(Line::Label(line.to_owned()));
     }
 
     fn write_double_operand(&mut self) {
@@ -433,23 +573,90 @@ impl<'a> CodeWriter<'a> {
         self.write("M=0");
     }
 
-    fn write_conditional(&mut self, condition: &str) {
+    fn write_conditional(&mut self, op_code: &OpCode) {
         self.write_double_operand();
-        self.write("D=M-D");
-        self.write("M=0");
-        self.write(&format!("@{}", self.lines_written + 5));
+
+        // symbolic jumps keep the code relocatable
+        let true_label = self.unique_label("cmp");
+        let condition = match op_code {
+            OpCode::Eq => {
+                // x - y is zero exactly when x == y, even when it wraps
+                self.write("D=M-D");
+                "D;JEQ"
+            }
+            OpCode::Gt => {
+                self.write_ordering(&true_label);
+                "D;JGT"
+            }
+            _ => {
+                self.write_ordering(&true_label);
+                "D;JLT"
+            }
+        };
+
+        self.write(&format!("@{}", true_label));
         self.write(condition);
         self.write("D=0");
-        self.write(&format!("@{}", self.lines_written + 3));
+        self.write(&format!("@{}.end", true_label));
         self.write("0;JMP");
-        self.write("D=-1")
+        self.label(&format!("({})", true_label));
+        self.write("D=-1");
+        self.label(&format!("({}.end)", true_label));
     }
 
-    fn gen_return_string() -> String {
-        rand::thread_rng()
-            .sample_iter(&Alphanumeric)
-            .take(7)
-            .map(char::from)
-            .collect()
+    /// Sets D to a value with the sign of `x - y`, for `x` in M and `y` in D
+    /// as left by `write_double_operand`. Subtracting overflows when the
+    /// signs differ, but then the sign of `x` alone orders them.
+    fn write_ordering(&mut self, label: &str) {
+        self.write(scratch::COMPARE_OPERAND);
+        self.write("M=D");
+        self.write("@SP");
+        self.write("A=M");
+        self.write("D=M");
+        self.write(&format!("@{}.neg", label));
+        self.write("D;JLT");
+
+        // x >= 0, so x > y if y is negative
+        self.write(scratch::COMPARE_OPERAND);
+        self.write("D=M");
+        self.write(&format!("@{}.same", label));
+        self.write("D;JGE");
+        self.write("D=1");
+        self.write(&format!("@{}.ordered", label));
+        self.write("0;JMP");
+
+        // x < 0, so x < y unless y is negative too
+        self.label(&format!("({}.neg)", label));
+        self.write(scratch::COMPARE_OPERAND);
+        self.write("D=M");
+        self.write(&format!("@{}.same", label));
+        self.write("D;JLT");
+        self.write("D=-1");
+        self.write(&format!("@{}.ordered", label));
+        self.write("0;JMP");
+
+        // with equal signs x - y can't overflow
+        self.label(&format!("({}.same)", label));
+        self.write(scratch::COMPARE_OPERAND);
+        self.write("D=M");
+        self.write("@SP");
+        self.write("A=M");
+        self.write("D=M-D");
+        self.label(&format!("({}.ordered)", label));
+    }
+
+    /// `Caller$$kind.N`, numbered per function so output is reproducible and
+    /// unique across files. VM symbols can't contain `$`, so no `Caller$label`
+    /// written for a VM label can collide with one.
+    fn unique_label(&mut self, kind: &'static str) -> String {
+        let func_name = self.get_current_func();
+        let counter = self
+            .label_counters
+            .entry((func_name.clone(), kind))
+            .or_insert(0);
+        let label = format!("{}$${}.{}", func_name, kind, counter);
+        *counter += 1;
+
+        label
     }
 }
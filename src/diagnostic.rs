@@ -0,0 +1,224 @@
+use crate::op_code::Segment;
+use std::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    UnknownCommand(String),
+    UnknownSegment(String),
+    MissingOperand {
+        command: String,
+        operand: &'static str,
+    },
+    NonNumericIndex {
+        operand: &'static str,
+        found: String,
+    },
+    ExtraTokens {
+        command: String,
+        found: String,
+    },
+    CountOutOfRange {
+        operand: &'static str,
+        found: String,
+        max: u32,
+    },
+    InvalidSymbol {
+        operand: &'static str,
+        found: String,
+    },
+    PopToConstant,
+    IndexOutOfRange {
+        segment: Segment,
+        index: u32,
+        max: u32,
+    },
+    ConstantOutOfRange {
+        value: u32,
+        max: u32,
+    },
+    UndefinedFunction(String),
+    DuplicateFunction {
+        name: String,
+        /// `file:line` of the first definition
+        previous: String,
+    },
+    UndefinedLabel {
+        label: String,
+        scope: String,
+    },
+    DuplicateLabel {
+        label: String,
+        scope: String,
+        previous_line: usize,
+    },
+}
+
+impl Display for DiagnosticKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownCommand(command) => write!(f, "unknown command `{}`", command),
+            Self::UnknownSegment(segment) => write!(f, "unknown segment `{}`", segment),
+            Self::MissingOperand { command, operand } => {
+                write!(f, "`{}` is missing its {} operand", command, operand)
+            }
+            Self::NonNumericIndex { operand, found } => {
+                write!(f, "expected a numeric {}, found `{}`", operand, found)
+            }
+            Self::ExtraTokens { command, found } => {
+                write!(
+                    f,
+                    "unexpected token `{}` after `{}` instruction",
+                    found, command
+                )
+            }
+            Self::CountOutOfRange {
+                operand,
+                found,
+                max,
+            } => write!(f, "{} `{}` is out of range (max {})", operand, found, max),
+            Self::InvalidSymbol { operand, found } => write!(
+                f,
+                "{} `{}` may only contain letters, digits, `_`, `.` and `:`, and not start with a digit",
+                operand, found
+            ),
+            Self::PopToConstant => write!(f, "cannot pop to the `constant` segment"),
+            Self::IndexOutOfRange {
+                segment,
+                index,
+                max,
+            } => write!(
+                f,
+                "index {} is out of range for segment `{}` (max {})",
+                index, segment, max
+            ),
+            Self::ConstantOutOfRange { value, max } => {
+                write!(f, "constant {} is out of range (max {})", value, max)
+            }
+            Self::UndefinedFunction(name) => {
+                write!(f, "call to undefined function `{}`", name)
+            }
+            Self::DuplicateFunction { name, previous } => {
+                write!(f, "function `{}` is already defined at {}", name, previous)
+            }
+            Self::UndefinedLabel { label, scope } => {
+                write!(f, "label `{}` is not defined in `{}`", label, scope)
+            }
+            Self::DuplicateLabel {
+                label,
+                scope,
+                previous_line,
+            } => write!(
+                f,
+                "label `{}` is already defined in `{}` on line {}",
+                label, scope, previous_line
+            ),
+        }
+    }
+}
+
+/// A single problem found in a .vm file, located by 1-based line and
+/// column so it can be reported compiler-style.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub filename: String,
+    pub line: usize,
+    pub column: usize,
+    pub len: usize,
+    pub source: String,
+    pub kind: DiagnosticKind,
+}
+
+impl Diagnostic {
+    /// Builds an error pointing at `text`, found at byte `offset` of the `source` line
+    pub fn error(
+        filename: &str,
+        line: usize,
+        source: &str,
+        (offset, text): (usize, &str),
+        kind: DiagnosticKind,
+    ) -> Self {
+        Self {
+            filename: filename.to_owned(),
+            line,
+            column: source[..offset].chars().count() + 1,
+            len: text.chars().count(),
+            source: source.to_owned(),
+            kind,
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+
+        writeln!(f, "error: {}", self.kind)?;
+        writeln!(
+            f,
+            "{}--> {}:{}:{}",
+            gutter, self.filename, self.line, self.column
+        )?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.source)?;
+        writeln!(
+            f,
+            "{} | {}{}",
+            gutter,
+            " ".repeat(self.column - 1),
+            "^".repeat(self.len.max(1))
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Diagnostics(Vec<Diagnostic>);
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.0.push(diagnostic)
+    }
+
+    pub fn extend(&mut self, other: Diagnostics) {
+        self.0.extend(other.0)
+    }
+
+    /// Stable sort, so diagnostics with equal keys keep their order
+    pub fn sort_by_key<K: Ord>(&mut self, key: impl FnMut(&Diagnostic) -> K) {
+        self.0.sort_by_key(key)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.0.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        !self.0.is_empty()
+    }
+}
+
+impl Display for Diagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, diagnostic) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", diagnostic)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostics {}
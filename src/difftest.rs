@@ -0,0 +1,370 @@
+use crate::{
+    assembler::Program,
+    bootstrap::Bootstrap,
+    code_writer::CodeWriter,
+    emulator::{Emulator, RAM_SIZE},
+    interpreter::Interpreter,
+    op_code::{Instruction, OpCode},
+    translator::{SourceFile, SourceInstructions},
+};
+use anyhow::{Context, Result};
+use std::{collections::HashSet, fmt::Display, ops::Range};
+
+// SP, LCL, ARG, THIS and THAT for programs without bootstrap code, as set
+// by the course's BasicTest.tst
+const INITIAL_POINTERS: [i16; 5] = [256, 300, 400, 3000, 3010];
+const POINTER_NAMES: [&str; 5] = ["SP", "LCL", "ARG", "THIS", "THAT"];
+const TEMP: u16 = 5;
+const STACK: u16 = 256;
+const HEAP: u16 = 2048;
+const HEAP_END: u16 = 16384;
+
+// the assembly for a single op code never needs anywhere near this many cycles
+const CYCLES_PER_OP_CODE: u64 = 10_000;
+
+/// A RAM location whose value differs between the two executions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub location: String,
+    /// value according to the interpreter
+    pub expected: i16,
+    /// value according to the emulated assembly
+    pub actual: i16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DivergenceKind {
+    /// the assembly never reached the code of the op code the interpreter ran next
+    ControlFlow { expected_address: u16 },
+    /// both reached the same point but memory differs
+    State(Vec<Mismatch>),
+}
+
+/// The first op code after which the two executions disagree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// number of op codes executed by the interpreter, including this one
+    pub step: u64,
+    /// path of the .vm file the op code is from
+    pub file: String,
+    pub line: usize,
+    pub op_code: String,
+    pub kind: DivergenceKind,
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "diverged at step {} after `{}` ({}:{})",
+            self.step, self.op_code, self.file, self.line
+        )?;
+
+        match &self.kind {
+            DivergenceKind::ControlFlow { expected_address } => writeln!(
+                f,
+                "  assembly never reached ROM[{}], the code for the next op code",
+                expected_address
+            ),
+            DivergenceKind::State(mismatches) => {
+                for mismatch in mismatches {
+                    writeln!(
+                        f,
+                        "  {}: interpreter {}, assembly {}",
+                        mismatch.location, mismatch.expected, mismatch.actual
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Agreed { steps: u64 },
+    Diverged(Divergence),
+}
+
+/// Runs a program through both the `Interpreter` and the `CodeWriter`
+/// output on the `Emulator`, comparing RAM after every op code.
+pub struct DiffTest<'a> {
+    files: Vec<SourceInstructions<'a>>,
+    /// path of each file, for reporting
+    paths: Vec<String>,
+    bootstrap: Option<Bootstrap>,
+    /// `(address, value)` pairs set in both machines before running
+    ram: Vec<(u16, i16)>,
+}
+
+impl<'a> DiffTest<'a> {
+    /// Tests `files`, parsed from `sources` in the same order
+    pub fn new(
+        sources: &[SourceFile],
+        files: Vec<SourceInstructions<'a>>,
+        bootstrap: Option<Bootstrap>,
+    ) -> Self {
+        Self {
+            files,
+            paths: sources
+                .iter()
+                .map(|source| source.parser.filename().to_owned())
+                .collect(),
+            bootstrap,
+            ram: Vec::new(),
+        }
+    }
+
+    /// Sets these RAM cells before running, after the default pointers of a
+    /// program without bootstrap code, like a test script's `set` commands
+    pub fn with_ram(mut self, ram: impl IntoIterator<Item = (u16, i16)>) -> Self {
+        self.ram.extend(ram);
+        self
+    }
+
+    /// Runs for at most `max_steps` op codes, stopping early when the program
+    /// halts or reaches `Sys.halt`.
+    pub fn run(&self, max_steps: u64) -> Result<Outcome> {
+        // where each op code's code starts in ROM, in interpreter program order
+        let mut addresses = Vec::new();
+        let mut asm = Vec::new();
+        let mut code_writer = CodeWriter::new(&mut asm, self.bootstrap.as_ref());
+        for (namespace, instructions) in &self.files {
+            code_writer.set_current_filename(namespace);
+            for instruction in instructions {
+                addresses.push(code_writer.instruction_count() as u16);
+                code_writer.write_op_code(&instruction.op_code)?;
+            }
+        }
+        // falling off the end of the program
+        addresses.push(code_writer.instruction_count() as u16);
+        code_writer.flush()?;
+
+        let asm = String::from_utf8(asm).context("Generated assembly is not UTF-8")?;
+        let mut emulator = Emulator::new(Program::assemble(&asm)?);
+
+        let mut interpreter = Interpreter::new(
+            self.files
+                .iter()
+                .map(|(namespace, instructions)| {
+                    let op_codes = instructions.iter().map(|i| i.op_code.clone()).collect();
+                    (namespace.clone(), op_codes)
+                })
+                .collect(),
+        )?;
+
+        if self.bootstrap.is_none() {
+            for (register, value) in INITIAL_POINTERS.into_iter().enumerate() {
+                interpreter.set_ram(register as u16, value);
+                emulator.set_ram(register as u16, value);
+            }
+        }
+        for &(address, value) in &self.ram {
+            interpreter.set_ram(address, value);
+            emulator.set_ram(address, value);
+        }
+        if let Some(bootstrap) = &self.bootstrap {
+            interpreter.bootstrap(bootstrap)?;
+        }
+
+        // the file path and instruction of each op code, in interpreter program order
+        let instructions: Vec<(&str, &Instruction)> = self
+            .files
+            .iter()
+            .zip(&self.paths)
+            .flat_map(|((_, instructions), path)| {
+                instructions.iter().map(move |i| (path.as_str(), i))
+            })
+            .collect();
+
+        // run the bootstrap code up to the entry function
+        if let Some(&address) = addresses.get(interpreter.pc()) {
+            if !Self::sync(&mut emulator, address) {
+                return Ok(Outcome::Diverged(Divergence {
+                    step: 0,
+                    file: "bootstrap".to_owned(),
+                    line: 0,
+                    op_code: "bootstrap".to_owned(),
+                    kind: DivergenceKind::ControlFlow {
+                        expected_address: address,
+                    },
+                }));
+            }
+        }
+
+        loop {
+            let halted = interpreter.is_halted()
+                || matches!(
+                    interpreter.current(),
+                    Some(OpCode::Function {
+                        func_name: "Sys.halt",
+                        ..
+                    })
+                );
+            if halted || interpreter.steps() >= max_steps {
+                return Ok(Outcome::Agreed {
+                    steps: interpreter.steps(),
+                });
+            }
+
+            let index = interpreter.pc();
+            // a `return` with no call active goes back to a frame the test set up
+            let outermost_return = matches!(instructions[index].1.op_code, OpCode::Return)
+                && interpreter.return_address_slots().is_empty();
+            interpreter.step()?;
+
+            let (file, instruction) = instructions[index];
+            let divergence = |kind| Divergence {
+                step: interpreter.steps(),
+                file: file.to_owned(),
+                line: instruction.line,
+                op_code: instruction.op_code.to_string(),
+                kind,
+            };
+
+            // returned from the bootstrap entry function
+            if self.bootstrap.is_some() && interpreter.pc() == instructions.len() {
+                return Ok(Outcome::Agreed {
+                    steps: interpreter.steps(),
+                });
+            }
+
+            // that frame's return address means something different to each
+            // machine, so only the state after returning is compared
+            if outermost_return {
+                Self::leave(&mut emulator, addresses[index]..addresses[index + 1]);
+                let mismatches = Self::compare(&interpreter, &emulator, true);
+                if !mismatches.is_empty() {
+                    return Ok(Outcome::Diverged(divergence(DivergenceKind::State(
+                        mismatches,
+                    ))));
+                }
+                return Ok(Outcome::Agreed {
+                    steps: interpreter.steps(),
+                });
+            }
+
+            // a return to an address that was never a return address leaves
+            // the program, which halts the interpreter
+            let Some(&expected_address) = addresses.get(interpreter.pc()) else {
+                return Ok(Outcome::Agreed {
+                    steps: interpreter.steps(),
+                });
+            };
+
+            if !Self::sync(&mut emulator, expected_address) {
+                return Ok(Outcome::Diverged(divergence(DivergenceKind::ControlFlow {
+                    expected_address,
+                })));
+            }
+
+            let mismatches = Self::compare(&interpreter, &emulator, interpreter.is_halted());
+            if !mismatches.is_empty() {
+                return Ok(Outcome::Diverged(divergence(DivergenceKind::State(
+                    mismatches,
+                ))));
+            }
+        }
+    }
+
+    /// Runs the emulator until it is about to execute `address`
+    fn sync(emulator: &mut Emulator, address: u16) -> bool {
+        for _ in 0..CYCLES_PER_OP_CODE {
+            if emulator.pc() == address {
+                return true;
+            }
+            if emulator.is_halted() {
+                return false;
+            }
+
+            emulator.step();
+        }
+
+        false
+    }
+
+    /// Runs the emulator until it jumps out of `code`
+    fn leave(emulator: &mut Emulator, code: Range<u16>) {
+        for _ in 0..CYCLES_PER_OP_CODE {
+            if !code.contains(&emulator.pc()) || emulator.is_halted() {
+                return;
+            }
+
+            emulator.step();
+        }
+    }
+
+    /// Pointers, temp, the live stack and statics, plus the heap when `full`.
+    /// Stack and heap cells are only compared once the interpreter has stored
+    /// to them, as popped and untouched cells hold whatever each left there.
+    fn compare(interpreter: &Interpreter, emulator: &Emulator, full: bool) -> Vec<Mismatch> {
+        let mut locations: Vec<(String, u16, u16)> = Vec::new();
+
+        for (register, name) in POINTER_NAMES.iter().enumerate() {
+            locations.push((name.to_string(), register as u16, register as u16));
+        }
+        for index in 0..8 {
+            locations.push((format!("temp {}", index), TEMP + index, TEMP + index));
+        }
+
+        // return addresses are ROM addresses in one and program indices in the other
+        let return_slots: HashSet<u16> =
+            interpreter.return_address_slots().iter().copied().collect();
+        let sp = interpreter.ram(0) as u16;
+        for address in STACK..sp.max(STACK) {
+            if interpreter.is_defined(address) && !return_slots.contains(&address) {
+                locations.push((format!("RAM[{}]", address), address, address));
+            }
+        }
+
+        let mut statics: Vec<_> = interpreter.statics().collect();
+        statics.sort();
+        for (namespace, index, address) in statics {
+            let name = format!("{}.{}", namespace, index);
+            if let Some(symbol) = emulator.symbol(&name) {
+                locations.push((format!("static {}", name), address, symbol));
+            }
+        }
+
+        if full {
+            for address in (HEAP..HEAP_END).filter(|&address| interpreter.is_defined(address)) {
+                locations.push((format!("RAM[{}]", address), address, address));
+            }
+        }
+
+        locations
+            .into_iter()
+            .filter_map(|(location, expected, actual)| {
+                let (expected, actual) = (interpreter.ram(expected), emulator.ram(actual));
+                (expected != actual).then_some(Mismatch {
+                    location,
+                    expected,
+                    actual,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Parses a `--set` assignment of a RAM cell, named `RAM[i]` or by one of the
+/// pointer registers, e.g. `LCL=300`
+pub fn parse_ram_setting(setting: &str) -> Result<(u16, i16)> {
+    let (name, value) = setting
+        .split_once('=')
+        .with_context(|| format!("expected `NAME=VALUE`, got `{}`", setting))?;
+
+    let address = match POINTER_NAMES.iter().position(|pointer| *pointer == name) {
+        Some(register) => register as u16,
+        None => name
+            .strip_prefix("RAM[")
+            .and_then(|index| index.strip_suffix(']'))
+            .and_then(|index| index.parse::<u16>().ok())
+            .filter(|&address| (address as usize) < RAM_SIZE)
+            .with_context(|| format!("unknown RAM cell `{}`", name))?,
+    };
+    let value = value
+        .parse::<i16>()
+        .with_context(|| format!("invalid value `{}`", value))?;
+
+    Ok((address, value))
+}
@@ -0,0 +1,183 @@
+use crate::assembler::{HackInstruction, Program};
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+pub const RAM_SIZE: usize = 32768;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// ran for the requested number of cycles
+    CycleLimit,
+    /// about to execute the instruction at this ROM address
+    Breakpoint(u16),
+    /// ran off the end of ROM or reached an `(END) @END 0;JMP` style loop
+    Halted,
+}
+
+/// A Hack CPU with its RAM and ROM
+pub struct Emulator {
+    rom: Vec<HackInstruction>,
+    ram: Vec<i16>,
+    a: i16,
+    d: i16,
+    pc: u16,
+    cycles: u64,
+    breakpoints: HashSet<u16>,
+    symbols: HashMap<String, u16>,
+}
+
+impl Emulator {
+    pub fn new(program: Program) -> Self {
+        Self {
+            rom: program.instructions,
+            ram: vec![0; RAM_SIZE],
+            a: 0,
+            d: 0,
+            pc: 0,
+            cycles: 0,
+            breakpoints: HashSet::new(),
+            symbols: program.symbols,
+        }
+    }
+
+    pub fn from_asm(source: &str) -> Result<Self> {
+        Ok(Self::new(Program::assemble(source)?))
+    }
+
+    pub fn ram(&self, address: u16) -> i16 {
+        self.ram[address as usize % RAM_SIZE]
+    }
+
+    pub fn set_ram(&mut self, address: u16, value: i16) {
+        self.ram[address as usize % RAM_SIZE] = value
+    }
+
+    pub fn ram_slice(&self, start: u16, len: usize) -> &[i16] {
+        &self.ram[start as usize..start as usize + len]
+    }
+
+    pub fn a(&self) -> i16 {
+        self.a
+    }
+
+    pub fn d(&self) -> i16 {
+        self.d
+    }
+
+    pub fn pc(&self) -> u16 {
+        self.pc
+    }
+
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    /// The address of a label or variable defined by the loaded program
+    pub fn symbol(&self, name: &str) -> Option<u16> {
+        self.symbols.get(name).copied()
+    }
+
+    pub fn add_breakpoint(&mut self, rom_address: u16) {
+        self.breakpoints.insert(rom_address);
+    }
+
+    pub fn remove_breakpoint(&mut self, rom_address: u16) {
+        self.breakpoints.remove(&rom_address);
+    }
+
+    /// Restarts execution from ROM address 0, leaving RAM untouched
+    pub fn reset(&mut self) {
+        self.pc = 0;
+        self.cycles = 0;
+    }
+
+    pub fn is_halted(&self) -> bool {
+        let pc = self.pc as usize;
+        if pc >= self.rom.len() {
+            return true;
+        }
+
+        // `@X` immediately followed by `0;JMP` back to the `@X`
+        pc > 0
+            && self.a as usize == pc - 1
+            && self.rom[pc - 1] == HackInstruction::A(pc as u16 - 1)
+            && matches!(self.rom[pc], HackInstruction::C { jump: 0b111, .. })
+    }
+
+    /// Runs until `max_cycles` instructions have executed, a breakpoint is
+    /// reached or the program halts. A breakpoint at the current PC is
+    /// ignored so execution can be resumed after stopping on it.
+    pub fn run(&mut self, max_cycles: u64) -> StopReason {
+        for cycle in 0..max_cycles {
+            if self.is_halted() {
+                return StopReason::Halted;
+            }
+            if cycle > 0 && self.breakpoints.contains(&self.pc) {
+                return StopReason::Breakpoint(self.pc);
+            }
+
+            self.step();
+        }
+
+        StopReason::CycleLimit
+    }
+
+    /// Executes a single instruction, doing nothing once halted
+    pub fn step(&mut self) {
+        let instruction = match self.rom.get(self.pc as usize) {
+            Some(instruction) => *instruction,
+            None => return,
+        };
+
+        self.cycles += 1;
+
+        match instruction {
+            HackInstruction::A(value) => {
+                self.a = value as i16;
+                self.pc += 1;
+            }
+            HackInstruction::C { dest, comp, jump } => {
+                let address = self.a as u16;
+                let y = if comp & 0b1000000 != 0 {
+                    self.ram(address)
+                } else {
+                    self.a
+                };
+                let out = Self::alu(self.d, y, comp);
+
+                if dest & 0b001 != 0 {
+                    self.set_ram(address, out);
+                }
+                if dest & 0b100 != 0 {
+                    self.a = out;
+                }
+                if dest & 0b010 != 0 {
+                    self.d = out;
+                }
+
+                let jump = (jump & 0b100 != 0 && out < 0)
+                    || (jump & 0b010 != 0 && out == 0)
+                    || (jump & 0b001 != 0 && out > 0);
+
+                self.pc = if jump { address } else { self.pc + 1 };
+            }
+        }
+    }
+
+    /// The Hack ALU, driven by the zx nx zy ny f no control bits
+    fn alu(x: i16, y: i16, comp: u8) -> i16 {
+        let bit = |n: u8| comp & (1 << n) != 0;
+
+        let x = if bit(5) { 0 } else { x };
+        let x = if bit(4) { !x } else { x };
+        let y = if bit(3) { 0 } else { y };
+        let y = if bit(2) { !y } else { y };
+        let out = if bit(1) { x.wrapping_add(y) } else { x & y };
+
+        if bit(0) {
+            !out
+        } else {
+            out
+        }
+    }
+}
@@ -0,0 +1,392 @@
+use crate::{
+    bootstrap::Bootstrap,
+    emulator::{StopReason, RAM_SIZE},
+    op_code::{OpCode, Segment, SegmentOpCode},
+};
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+
+const SP: u16 = 0;
+const LCL: u16 = 1;
+const ARG: u16 = 2;
+const THIS: u16 = 3;
+const THAT: u16 = 4;
+const TEMP: u16 = 5;
+const STATIC: u16 = 16;
+
+/// An op code placed in the flattened program
+struct LoadedOpCode<'a> {
+    op_code: OpCode<'a>,
+    /// static namespace of the file it came from
+    file: usize,
+    /// resolved program index of a `goto`, `if-goto` or `call` target
+    target: Option<usize>,
+}
+
+/// Executes VM op codes directly over the same memory model the translated
+/// assembly uses, as a reference for what `CodeWriter` output should do.
+pub struct Interpreter<'a> {
+    program: Vec<LoadedOpCode<'a>>,
+    files: Vec<String>,
+    statics: HashMap<(usize, u32), u16>,
+    ram: Vec<i16>,
+    /// RAM cells holding a value that was stored and not popped since
+    defined: Vec<bool>,
+    pc: usize,
+    steps: u64,
+    functions: HashMap<String, usize>,
+    /// stack addresses holding the return address of every active call
+    frames: Vec<u16>,
+}
+
+impl<'a> Interpreter<'a> {
+    /// Loads `(static namespace, op codes)` pairs in order as one program,
+    /// resolving every label and call target up front.
+    pub fn new(files: Vec<(String, Vec<OpCode<'a>>)>) -> Result<Self> {
+        let mut program = Vec::new();
+        let mut names = Vec::new();
+        let mut functions = HashMap::new();
+        let mut labels = HashMap::new();
+        let mut statics = HashMap::new();
+
+        for (name, op_codes) in files {
+            // files sharing a namespace share their statics
+            let file = match names.iter().position(|existing| *existing == name) {
+                Some(file) => file,
+                None => {
+                    names.push(name.clone());
+                    names.len() - 1
+                }
+            };
+
+            // labels before any function are scoped to the file
+            let mut scope = name.clone();
+
+            for op_code in op_codes {
+                match &op_code {
+                    OpCode::Function { func_name, .. } => {
+                        scope = func_name.to_string();
+                        if functions.insert(scope.clone(), program.len()).is_some() {
+                            bail!("function `{}` is defined more than once", func_name);
+                        }
+                    }
+                    OpCode::Label(op_code) => {
+                        labels.insert((scope.clone(), op_code.label.to_owned()), program.len());
+                    }
+                    OpCode::Push(SegmentOpCode {
+                        segment: Segment::Static,
+                        offset,
+                    })
+                    | OpCode::Pop(SegmentOpCode {
+                        segment: Segment::Static,
+                        offset,
+                    }) => {
+                        let next = STATIC + statics.len() as u16;
+                        statics.entry((file, *offset)).or_insert(next);
+                    }
+                    _ => {}
+                }
+
+                program.push((
+                    scope.clone(),
+                    LoadedOpCode {
+                        op_code,
+                        file,
+                        target: None,
+                    },
+                ));
+            }
+        }
+
+        let program = program
+            .into_iter()
+            .map(|(scope, mut loaded)| {
+                loaded.target = match &loaded.op_code {
+                    OpCode::Goto(op_code) | OpCode::If(op_code) => {
+                        match labels.get(&(scope, op_code.label.to_owned())) {
+                            Some(target) => Some(*target),
+                            None => bail!("label `{}` is not defined", op_code.label),
+                        }
+                    }
+                    OpCode::Call { func_name, .. } => match functions.get(*func_name) {
+                        Some(target) => Some(*target),
+                        None => bail!("function `{}` is not defined", func_name),
+                    },
+                    _ => None,
+                };
+
+                Ok(loaded)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            program,
+            files: names,
+            statics,
+            ram: vec![0; RAM_SIZE],
+            defined: vec![false; RAM_SIZE],
+            pc: 0,
+            steps: 0,
+            frames: Vec::new(),
+            functions,
+        })
+    }
+
+    /// Sets up the stack like the translated bootstrap code and calls the
+    /// entry function. Returning from it halts the interpreter.
+    pub fn bootstrap(&mut self, bootstrap: &Bootstrap) -> Result<()> {
+        self.set_ram(SP, bootstrap.stack_base as i16);
+        if let Some(pointers) = bootstrap.segment_pointers {
+            for (register, value) in [LCL, ARG, THIS, THAT].into_iter().zip(pointers) {
+                self.set_ram(register, value);
+            }
+        }
+
+        let entry = match self.functions.get(&bootstrap.entry) {
+            Some(entry) => *entry,
+            None => bail!("entry function `{}` is not defined", bootstrap.entry),
+        };
+        self.call(entry, 0, self.program.len());
+
+        Ok(())
+    }
+
+    /// Continues execution from the start of a function without calling it,
+    /// like the course's VM emulator does with `Sys.init`
+    pub fn start_at(&mut self, func_name: &str) -> Result<()> {
+        match self.functions.get(func_name) {
+            Some(function) => self.pc = *function,
+            None => bail!("function `{}` is not defined", func_name),
+        }
+
+        Ok(())
+    }
+
+    pub fn has_function(&self, func_name: &str) -> bool {
+        self.functions.contains_key(func_name)
+    }
+
+    pub fn ram(&self, address: u16) -> i16 {
+        self.ram[address as usize % RAM_SIZE]
+    }
+
+    pub fn set_ram(&mut self, address: u16, value: i16) {
+        self.ram[address as usize % RAM_SIZE] = value;
+        self.defined[address as usize % RAM_SIZE] = true;
+    }
+
+    /// Whether `address` holds a value that was set or stored and hasn't been
+    /// popped off the stack since, rather than one left over from earlier
+    pub fn is_defined(&self, address: u16) -> bool {
+        self.defined[address as usize % RAM_SIZE]
+    }
+
+    pub fn ram_slice(&self, start: u16, len: usize) -> &[i16] {
+        &self.ram[start as usize..start as usize + len]
+    }
+
+    /// Index into the loaded program of the next op code to execute
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// The next op code to execute, if not halted
+    pub fn current(&self) -> Option<&OpCode<'a>> {
+        self.program.get(self.pc).map(|loaded| &loaded.op_code)
+    }
+
+    /// Stack addresses holding the return address of every active call, which
+    /// unlike the rest of the frame differ from the translated program's.
+    pub fn return_address_slots(&self) -> &[u16] {
+        &self.frames
+    }
+
+    /// RAM address given to `static index` of the named file
+    pub fn static_address(&self, namespace: &str, index: u32) -> Option<u16> {
+        let file = self.files.iter().position(|name| name == namespace)?;
+        self.statics.get(&(file, index)).copied()
+    }
+
+    /// Every static variable as `(namespace, index, address)`
+    pub fn statics(&self) -> impl Iterator<Item = (&str, u32, u16)> {
+        self.statics
+            .iter()
+            .map(|(&(file, index), &address)| (self.files[file].as_str(), index, address))
+    }
+
+    /// Halted once execution leaves the program, returns from the bootstrap
+    /// entry or reaches a `label X; goto X` loop such as `Sys.halt`'s.
+    pub fn is_halted(&self) -> bool {
+        match self.program.get(self.pc) {
+            None => true,
+            Some(loaded) => {
+                matches!(loaded.op_code, OpCode::Goto(_))
+                    && self.pc > 0
+                    && loaded.target == Some(self.pc - 1)
+            }
+        }
+    }
+
+    pub fn run(&mut self, max_steps: u64) -> Result<StopReason> {
+        for _ in 0..max_steps {
+            if self.is_halted() {
+                return Ok(StopReason::Halted);
+            }
+
+            self.step()?;
+        }
+
+        Ok(StopReason::CycleLimit)
+    }
+
+    /// Executes a single op code, doing nothing once halted
+    pub fn step(&mut self) -> Result<()> {
+        let loaded = match self.program.get(self.pc) {
+            Some(loaded) => loaded,
+            None => return Ok(()),
+        };
+        let (file, target) = (loaded.file, loaded.target);
+
+        self.steps += 1;
+        self.pc += 1;
+
+        match &loaded.op_code {
+            OpCode::Add => self.binary(|x, y| x.wrapping_add(y)),
+            OpCode::Sub => self.binary(|x, y| x.wrapping_sub(y)),
+            OpCode::And => self.binary(|x, y| x & y),
+            OpCode::Or => self.binary(|x, y| x | y),
+            OpCode::Eq => self.binary(|x, y| -((x == y) as i16)),
+            OpCode::Gt => self.binary(|x, y| -((x > y) as i16)),
+            OpCode::Lt => self.binary(|x, y| -((x < y) as i16)),
+            OpCode::Neg => {
+                let value = self.pop();
+                self.push(value.wrapping_neg())
+            }
+            OpCode::Not => {
+                let value = self.pop();
+                self.push(!value)
+            }
+            OpCode::Push(op_code) => {
+                let value = match op_code.segment {
+                    Segment::Constant => op_code.offset as i16,
+                    _ => self.ram(self.address(file, op_code)?),
+                };
+                self.push(value)
+            }
+            OpCode::Pop(op_code) => {
+                if op_code.segment == Segment::Constant {
+                    bail!("cannot pop to the `constant` segment");
+                }
+                let address = self.address(file, op_code)?;
+                let value = self.pop();
+                self.set_ram(address, value)
+            }
+            OpCode::Label(_) => {}
+            OpCode::Goto(_) => self.pc = target.unwrap(),
+            OpCode::If(_) => {
+                if self.pop() != 0 {
+                    self.pc = target.unwrap()
+                }
+            }
+            OpCode::Call { num_args, .. } => {
+                let (num_args, return_index) = (*num_args, self.pc);
+                self.call(target.unwrap(), num_args, return_index)
+            }
+            OpCode::Function { num_locals, .. } => {
+                for _ in 0..*num_locals {
+                    self.push(0)
+                }
+            }
+            OpCode::Return => self.ret(),
+        }
+
+        Ok(())
+    }
+
+    fn push(&mut self, value: i16) {
+        let sp = self.ram(SP);
+        self.set_ram(sp as u16, value);
+        self.set_ram(SP, sp.wrapping_add(1));
+    }
+
+    fn pop(&mut self) -> i16 {
+        let sp = self.ram(SP).wrapping_sub(1);
+        self.set_ram(SP, sp);
+        self.defined[sp as u16 as usize % RAM_SIZE] = false;
+        self.ram(sp as u16)
+    }
+
+    fn binary(&mut self, op: impl Fn(i16, i16) -> i16) {
+        let y = self.pop();
+        let x = self.pop();
+        self.push(op(x, y))
+    }
+
+    /// RAM address of a segment entry, failing on a `pointer` or `temp` index
+    /// past the end of its segment, which the validator would have rejected
+    fn address(&self, file: usize, op_code: &SegmentOpCode) -> Result<u16> {
+        let base = |register| self.ram(register) as u16;
+        let offset = op_code.offset as u16;
+        let fixed = |start: u16, len: u32| {
+            if op_code.offset >= len {
+                bail!(
+                    "`{} {}` is outside the segment's {} entries",
+                    op_code.segment,
+                    op_code.offset,
+                    len
+                );
+            }
+            Ok(start + offset)
+        };
+
+        let address = match op_code.segment {
+            Segment::Local => base(LCL).wrapping_add(offset),
+            Segment::Argument => base(ARG).wrapping_add(offset),
+            Segment::This => base(THIS).wrapping_add(offset),
+            Segment::That => base(THAT).wrapping_add(offset),
+            Segment::Pointer => fixed(THIS, 2)?,
+            Segment::Temp => fixed(TEMP, 8)?,
+            Segment::Static => self.statics[&(file, op_code.offset)],
+            Segment::Constant => bail!("the `constant` segment has no address"),
+        };
+
+        Ok(address)
+    }
+
+    fn call(&mut self, function: usize, num_args: u8, return_index: usize) {
+        let frame = self.ram(SP) as u16;
+        self.frames.push(frame);
+
+        self.push(return_index as i16);
+        for register in [LCL, ARG, THIS, THAT] {
+            self.push(self.ram(register));
+        }
+
+        // ARG = SP-n-5, LCL = SP
+        self.set_ram(ARG, frame.wrapping_sub(num_args as u16) as i16);
+        self.set_ram(LCL, self.ram(SP));
+        self.pc = function;
+    }
+
+    fn ret(&mut self) {
+        let frame = self.ram(LCL) as u16;
+        let return_index = self.ram(frame.wrapping_sub(5)) as u16 as usize;
+
+        // *ARG = pop(), SP = ARG+1
+        let value = self.pop();
+        let arg = self.ram(ARG) as u16;
+        self.set_ram(arg, value);
+        self.set_ram(SP, arg.wrapping_add(1) as i16);
+
+        for (offset, register) in [THAT, THIS, ARG, LCL].into_iter().enumerate() {
+            self.set_ram(register, self.ram(frame.wrapping_sub(offset as u16 + 1)));
+        }
+
+        self.frames.pop();
+        self.pc = return_index;
+    }
+}
@@ -1,4 +1,16 @@
+pub mod assembler;
+pub mod bootstrap;
+pub mod call_graph;
 pub mod code_writer;
+pub mod diagnostic;
+pub mod difftest;
+pub mod emulator;
+pub mod interpreter;
+pub mod linker;
 pub mod op_code;
 pub mod parser;
+pub mod peephole;
+pub mod test_script;
 pub mod translator;
+pub mod validator;
+pub mod vm_optimizer;
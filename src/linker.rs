@@ -0,0 +1,138 @@
+use crate::{
+    diagnostic::{Diagnostic, DiagnosticKind, Diagnostics},
+    op_code::{Instruction, OpCode},
+    parser::Parser,
+};
+use std::collections::{hash_map::Entry, HashMap};
+
+/// Whole-program checks once every file is parsed: each function is defined
+/// once, each call names a defined function, and each jump names a label
+/// declared once in its function.
+#[derive(Default)]
+pub struct Linker<'a> {
+    files: Vec<(&'a str, &'a [Instruction<'a>])>,
+}
+
+/// Where a function or label was first declared
+type Declaration<'a> = (&'a str, usize);
+
+impl<'a> Linker<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_file(&mut self, filename: &'a str, instructions: &'a [Instruction<'a>]) {
+        self.files.push((filename, instructions));
+    }
+
+    pub fn link(&self) -> Diagnostics {
+        let mut diagnostics = Diagnostics::new();
+        let mut functions: HashMap<&str, Declaration> = HashMap::new();
+
+        for &(filename, instructions) in &self.files {
+            for instruction in instructions {
+                if let OpCode::Function { func_name, .. } = instruction.op_code {
+                    match functions.entry(func_name) {
+                        Entry::Occupied(previous) => {
+                            let (file, line) = previous.get();
+                            diagnostics.push(Self::error(
+                                filename,
+                                instruction,
+                                DiagnosticKind::DuplicateFunction {
+                                    name: func_name.to_owned(),
+                                    previous: format!("{}:{}", file, line),
+                                },
+                            ));
+                        }
+                        Entry::Vacant(entry) => {
+                            entry.insert((filename, instruction.line));
+                        }
+                    }
+                }
+            }
+        }
+
+        for &(filename, instructions) in &self.files {
+            for instruction in instructions {
+                if let OpCode::Call { func_name, .. } = instruction.op_code {
+                    if !functions.contains_key(func_name) {
+                        diagnostics.push(Self::error(
+                            filename,
+                            instruction,
+                            DiagnosticKind::UndefinedFunction(func_name.to_owned()),
+                        ));
+                    }
+                }
+            }
+
+            diagnostics.extend(Self::link_labels(filename, instructions));
+        }
+
+        diagnostics
+    }
+
+    /// Labels are scoped to their function, or to the file before any function
+    fn link_labels(filename: &str, instructions: &[Instruction]) -> Diagnostics {
+        let mut diagnostics = Diagnostics::new();
+        let mut scopes: Vec<(&str, Vec<&Instruction>)> = vec![(filename, Vec::new())];
+
+        for instruction in instructions {
+            if let OpCode::Function { func_name, .. } = instruction.op_code {
+                scopes.push((func_name, Vec::new()));
+            }
+            scopes.last_mut().unwrap().1.push(instruction);
+        }
+
+        for (scope, instructions) in scopes {
+            let mut labels: HashMap<&str, usize> = HashMap::new();
+
+            for instruction in &instructions {
+                if let OpCode::Label(op_code) = &instruction.op_code {
+                    match labels.entry(op_code.label) {
+                        Entry::Occupied(previous) => diagnostics.push(Self::error(
+                            filename,
+                            instruction,
+                            DiagnosticKind::DuplicateLabel {
+                                label: op_code.label.to_owned(),
+                                scope: scope.to_owned(),
+                                previous_line: *previous.get(),
+                            },
+                        )),
+                        Entry::Vacant(entry) => {
+                            entry.insert(instruction.line);
+                        }
+                    }
+                }
+            }
+
+            for instruction in &instructions {
+                if let OpCode::Goto(op_code) | OpCode::If(op_code) = &instruction.op_code {
+                    if !labels.contains_key(op_code.label) {
+                        diagnostics.push(Self::error(
+                            filename,
+                            instruction,
+                            DiagnosticKind::UndefinedLabel {
+                                label: op_code.label.to_owned(),
+                                scope: scope.to_owned(),
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Reports `kind` at the name operand of the instruction
+    fn error(filename: &str, instruction: &Instruction, kind: DiagnosticKind) -> Diagnostic {
+        let tokens = Parser::tokenize(instruction.source);
+        Diagnostic::error(
+            filename,
+            instruction.line,
+            instruction.source,
+            tokens[1],
+            kind,
+        )
+    }
+}
@@ -1,23 +1,312 @@
 use anyhow::{Context, Result};
-use clap::Parser;
-use vm_translator::translator::Translator;
+use clap::{Parser, Subcommand};
+use std::{fs, path::Path, process};
+use vm_translator::{
+    bootstrap::Bootstrap,
+    call_graph::GraphFormat,
+    diagnostic::Diagnostics,
+    difftest::{parse_ram_setting, DiffTest, Outcome},
+    op_code::OpCode,
+    test_script::TestScript,
+    translator::{Emit, SourceOptions, Translator, TranslatorOptions},
+};
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Args {
-    #[arg(short, long)]
-    input: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// A .vm file or a directory of .vm files, translated together with the
+    /// other inputs, or `-` for standard input
+    #[arg(short, long, required = true)]
+    input: Vec<String>,
 
+    /// Where to write the program, `-` for standard output. Defaults to
+    /// `Dir/Dir.asm` for a directory and `File.asm` for a file
     #[arg(short, long)]
-    output: String,
+    output: Option<String>,
 
+    /// Emit bootstrap code that sets up the stack and calls the entry function
     #[arg(short, long)]
     bootstrap: bool,
+
+    /// Initial stack pointer set by the bootstrap code
+    #[arg(long, default_value_t = 256)]
+    stack_base: u16,
+
+    /// Also initialise LCL, ARG, THIS and THAT to the course's sentinel values
+    #[arg(long)]
+    init_segments: bool,
+
+    /// Function called by the bootstrap code, and that --strip-unused keeps
+    /// everything reachable from [default: Sys.init]
+    #[arg(long)]
+    entry: Option<String>,
+
+    /// Write Hack assembly or assemble it straight to machine code
+    #[arg(long, value_enum, default_value_t = Emit::Asm)]
+    emit: Emit,
+
+    /// Jump to one shared copy of the call and return code instead of
+    /// inlining it at every call site
+    #[arg(long)]
+    shared_routines: bool,
+
+    /// Optimisation level: 1 folds constants and removes dead stores and
+    /// redundant loads, 2 also fuses pushes into the pops that follow them
+    #[arg(short = 'O', default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=2))]
+    opt_level: u8,
+
+    /// Leave out functions that can't be reached from the entry function
+    #[arg(long)]
+    strip_unused: bool,
+
+    /// Function to keep with --strip-unused even if nothing calls it
+    #[arg(long, value_name = "FUNCTION", value_delimiter = ',')]
+    keep: Vec<String>,
+
+    /// Prefix each file's static variables with its directory name, for
+    /// programs whose files share a name across directories
+    #[arg(long)]
+    prefix_namespaces: bool,
+
+    /// Also translate .vm files in subdirectories of the input directory
+    #[arg(short, long)]
+    recursive: bool,
+
+    /// Only translate files matching this glob, matched against the file name
+    /// or, if it has a `/`, the path within the input directory
+    #[arg(long, value_name = "GLOB")]
+    include: Vec<String>,
+
+    /// Skip files matching this glob, matched like --include
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Search this directory for functions the inputs call but don't
+    /// define, translating only the files that define them
+    #[arg(short = 'L', value_name = "DIR")]
+    library: Vec<String>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run a program through the VM interpreter and the translated assembly,
+    /// comparing RAM after every op code
+    Difftest {
+        /// A .vm file or a directory of .vm files
+        dir: String,
+
+        /// Stop after this many op codes if the program hasn't halted
+        #[arg(long, default_value_t = 1_000_000)]
+        steps: u64,
+
+        /// Set RAM cells before running, e.g. `SP=256,LCL=300,RAM[400]=6`.
+        /// Without bootstrap code, SP, LCL, ARG, THIS and THAT otherwise
+        /// start as in the course's BasicTest.tst.
+        #[arg(long, value_delimiter = ',', value_parser = parse_ram_setting)]
+        set: Vec<(u16, i16)>,
+    },
+    /// Run a nand2tetris .tst script, checking its output against the .cmp
+    /// file it names
+    Test {
+        /// The .tst script
+        script: String,
+    },
+    /// Print which functions call which, with call counts and where each
+    /// function is defined
+    Callgraph {
+        /// .vm files or directories of .vm files, graphed as one program
+        #[arg(required = true)]
+        inputs: Vec<String>,
+
+        /// Search this directory for functions the inputs call but don't
+        /// define. Functions found nowhere are shown as external.
+        #[arg(short = 'L', value_name = "DIR")]
+        library: Vec<String>,
+
+        #[arg(long, value_enum, default_value_t = GraphFormat::Dot)]
+        format: GraphFormat,
+
+        /// Write the graph to this file instead of standard output
+        #[arg(short, long)]
+        output: Option<String>,
+    },
 }
 
 fn main() -> Result<()> {
     let cfg = Args::parse();
-    let mut translator = Translator::new(cfg.input, cfg.output, cfg.bootstrap);
-    translator.translate().context("Error during translation")?;
+
+    let result = match cfg.command {
+        Some(Command::Difftest {
+            ref dir,
+            steps,
+            ref set,
+        }) => difftest(dir, steps, set),
+        Some(Command::Test { ref script }) => test(script),
+        Some(Command::Callgraph {
+            ref inputs,
+            ref library,
+            format,
+            ref output,
+        }) => callgraph(inputs, library, format, output.as_deref()),
+        None => translate(cfg),
+    };
+
+    if let Err(err) = result {
+        // diagnostics are already formatted compiler-style
+        if let Some(diagnostics) = err.downcast_ref::<Diagnostics>() {
+            eprint!("{}", diagnostics);
+            eprintln!(
+                "\nerror: translation failed due to {} previous error(s)",
+                diagnostics.error_count()
+            );
+            process::exit(1);
+        }
+
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+fn translate(cfg: Args) -> Result<()> {
+    let bootstrap = cfg.bootstrap.then(|| {
+        let entry = cfg.entry.as_deref().unwrap_or("Sys.init");
+        let bootstrap = Bootstrap::new(cfg.stack_base, entry);
+        if cfg.init_segments {
+            bootstrap.with_sentinels()
+        } else {
+            bootstrap
+        }
+    });
+    // the bootstrapped entry's file comes first
+    let entry_file = bootstrap.as_ref().map(|bootstrap| bootstrap.entry.clone());
+    let options = TranslatorOptions {
+        bootstrap,
+        emit: cfg.emit,
+        shared_routines: cfg.shared_routines,
+        opt_level: cfg.opt_level,
+        strip_unused: cfg.strip_unused,
+        entry: cfg.entry,
+        keep: cfg.keep,
+        sources: SourceOptions {
+            prefix_namespaces: cfg.prefix_namespaces,
+            recursive: cfg.recursive,
+            include: cfg.include,
+            exclude: cfg.exclude,
+            entry: entry_file,
+            libraries: cfg.library,
+        },
+    };
+    let mut inputs = cfg.input.into_iter();
+    let input = inputs.next().unwrap();
+    let output = match cfg.output {
+        Some(output) => output,
+        None => Translator::default_output(&input, cfg.emit)?,
+    };
+    let mut translator = Translator::new(input, output, options).with_inputs(inputs);
+
+    let translation = translator.translate().context("Error during translation")?;
+
+    if !translation.removed_functions.is_empty() {
+        eprintln!(
+            "removed {} unused function(s): {}",
+            translation.removed_functions.len(),
+            translation.removed_functions.join(", ")
+        );
+    }
+    if let Some(baseline) = translation.baseline_instruction_count {
+        eprintln!(
+            "{} instructions, down from {}",
+            translation.instruction_count, baseline
+        );
+    }
+
+    Ok(())
+}
+
+fn difftest(dir: &str, steps: u64, ram: &[(u16, i16)]) -> Result<()> {
+    let sources = Translator::load_sources(&[dir], &SourceOptions::default())?;
+    let (files, diagnostics) = Translator::parse_sources(&sources);
+    if diagnostics.has_errors() {
+        return Err(diagnostics.into());
+    }
+    eprint!("{}", diagnostics);
+
+    // like the course's test scripts, bootstrap only programs that have a Sys.init
+    let has_sys_init = files.iter().flat_map(|(_, i)| i).any(|instruction| {
+        matches!(
+            instruction.op_code,
+            OpCode::Function {
+                func_name: "Sys.init",
+                ..
+            }
+        )
+    });
+    let bootstrap = has_sys_init.then(Bootstrap::default);
+
+    let difftest = DiffTest::new(&sources, files, bootstrap).with_ram(ram.iter().copied());
+    match difftest.run(steps)? {
+        Outcome::Agreed { steps } => {
+            println!("interpreter and assembly agree after {} steps", steps);
+            Ok(())
+        }
+        Outcome::Diverged(divergence) => {
+            print!("{}", divergence);
+            process::exit(1);
+        }
+    }
+}
+
+fn test(script: &str) -> Result<()> {
+    let report = TestScript::load(Path::new(script))?.run()?;
+    if report.passed() {
+        println!("{}: comparison ended successfully", script);
+        return Ok(());
+    }
+
+    for mismatch in &report.mismatches {
+        println!("comparison failure at line {}", mismatch.line);
+        println!("  expected: {}", mismatch.expected);
+        println!("  actual:   {}", mismatch.actual);
+    }
+    process::exit(1);
+}
+
+fn callgraph(
+    inputs: &[String],
+    library: &[String],
+    format: GraphFormat,
+    output: Option<&str>,
+) -> Result<()> {
+    let options = SourceOptions {
+        libraries: library.to_vec(),
+        ..SourceOptions::default()
+    };
+    let inputs: Vec<&str> = inputs.iter().map(String::as_str).collect();
+    let sources = Translator::load_sources(&inputs, &options)?;
+
+    // an incomplete program still has a graph, calls out of it are external
+    let (files, diagnostics) = Translator::parse_files(&sources);
+    if diagnostics.has_errors() {
+        return Err(diagnostics.into());
+    }
+    eprint!("{}", diagnostics);
+
+    let graph = Translator::call_graph(&sources, &files).export(format);
+    match output {
+        Some(output) => fs::write(output, graph).context("Error writing call graph")?,
+        None => print!("{}", graph),
+    }
+
     Ok(())
 }
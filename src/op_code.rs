@@ -1,20 +1,69 @@
 use std::fmt::Display;
 
-pub struct SegmentOpCode<'a> {
-    pub segment: &'a str,
-    pub offset: u32,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Segment {
+    Argument,
+    Local,
+    Static,
+    Constant,
+    This,
+    That,
+    Pointer,
+    Temp,
 }
 
-impl<'a> SegmentOpCode<'a> {
+impl Segment {
+    pub fn from_name(name: &str) -> Option<Self> {
+        let segment = match name {
+            "argument" => Self::Argument,
+            "local" => Self::Local,
+            "static" => Self::Static,
+            "constant" => Self::Constant,
+            "this" => Self::This,
+            "that" => Self::That,
+            "pointer" => Self::Pointer,
+            "temp" => Self::Temp,
+            _ => return None,
+        };
+
+        Some(segment)
+    }
+
+    /// Segments whose base address is held in a pointer register
     pub fn is_scoped_segment(&self) -> bool {
-        ["local", "argument", "this", "that"].contains(&self.segment)
+        matches!(self, Self::Local | Self::Argument | Self::This | Self::That)
+    }
+}
+
+impl Display for Segment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::Argument => "argument",
+            Self::Local => "local",
+            Self::Static => "static",
+            Self::Constant => "constant",
+            Self::This => "this",
+            Self::That => "that",
+            Self::Pointer => "pointer",
+            Self::Temp => "temp",
+        };
+
+        write!(f, "{}", v)
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentOpCode {
+    pub segment: Segment,
+    pub offset: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LabelOpCode<'a> {
     pub label: &'a str,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum OpCode<'a> {
     Add,
     Sub,
@@ -25,8 +74,8 @@ pub enum OpCode<'a> {
     And,
     Or,
     Not,
-    Push(SegmentOpCode<'a>),
-    Pop(SegmentOpCode<'a>),
+    Push(SegmentOpCode),
+    Pop(SegmentOpCode),
     Label(LabelOpCode<'a>),
     Goto(LabelOpCode<'a>),
     If(LabelOpCode<'a>),
@@ -35,6 +84,13 @@ pub enum OpCode<'a> {
     Function { func_name: &'a str, num_locals: u8 },
 }
 
+/// An op code together with where it was parsed from
+pub struct Instruction<'a> {
+    pub op_code: OpCode<'a>,
+    pub line: usize,
+    pub source: &'a str,
+}
+
 impl Display for OpCode<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let v = match self {
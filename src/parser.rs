@@ -1,113 +1,244 @@
-use crate::op_code::{LabelOpCode, OpCode, SegmentOpCode};
+use crate::{
+    diagnostic::{Diagnostic, DiagnosticKind, Diagnostics},
+    op_code::{Instruction, LabelOpCode, OpCode, Segment, SegmentOpCode},
+};
 use std::io::Read;
 
 pub struct Parser {
+    filename: String,
     content: String,
 }
 
+/// A whitespace separated word of an instruction and its byte offset in the line
+pub(crate) type Token<'a> = (usize, &'a str);
+
+/// An error kind and the token it should be reported at
+type LocatedError<'a> = (Token<'a>, DiagnosticKind);
+
 impl Parser {
-    pub fn new(mut stream: impl Read) -> Self {
+    pub fn new(filename: &str, mut stream: impl Read) -> Self {
         let mut content = String::new();
         let _ = stream.read_to_string(&mut content);
 
-        Self { content }
+        Self {
+            filename: filename.to_owned(),
+            content,
+        }
     }
 
-    pub fn parse(&self) -> Vec<OpCode<'_>> {
-        let mut instructions: Vec<OpCode<'_>> = Vec::new();
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
 
-        for line in self.content.lines() {
-            // strip comments and empty spaces
-            let instruction = line.trim().splitn(2, "/").next().unwrap().trim();
-            if instruction.is_empty() {
-                continue;
-            }
+    /// Parses every line of the file, recovering from bad lines by skipping
+    /// them, and returns the op codes that parsed along with all diagnostics.
+    pub fn parse(&self) -> (Vec<Instruction<'_>>, Diagnostics) {
+        let mut instructions: Vec<Instruction<'_>> = Vec::new();
+        let mut diagnostics = Diagnostics::new();
 
-            if instruction.starts_with("push") {
-                let parts = instruction.splitn(3, " ").collect::<Vec<&str>>();
-                let push_op_code = SegmentOpCode {
-                    segment: parts[1],
-                    offset: parts[2]
-                        .parse::<u32>()
-                        .expect("Invalid index pass to push instruction"),
-                };
-                instructions.push(OpCode::Push(push_op_code));
+        for (index, line) in self.content.lines().enumerate() {
+            let tokens = Self::tokenize(line);
+            if tokens.is_empty() {
                 continue;
             }
 
-            if instruction.starts_with("pop") {
-                let parts = instruction.splitn(3, " ").collect::<Vec<&str>>();
-                let pop_op_code = SegmentOpCode {
-                    segment: parts[1],
-                    offset: parts[2]
-                        .parse::<u32>()
-                        .expect("Invalid index pass to pop instruction"),
-                };
-                instructions.push(OpCode::Pop(pop_op_code));
-                continue;
+            match Self::parse_instruction(&tokens) {
+                Ok(op_code) => instructions.push(Instruction {
+                    op_code,
+                    line: index + 1,
+                    source: line,
+                }),
+                Err((token, kind)) => diagnostics.push(Diagnostic::error(
+                    &self.filename,
+                    index + 1,
+                    line,
+                    token,
+                    kind,
+                )),
             }
+        }
 
-            if instruction.starts_with("label") {
-                let parts = instruction.splitn(2, " ").collect::<Vec<&str>>();
-                let label_op_code = LabelOpCode { label: parts[1] };
-                instructions.push(OpCode::Label(label_op_code));
-                continue;
-            }
+        (instructions, diagnostics)
+    }
 
-            if instruction.starts_with("goto") {
-                let parts = instruction.splitn(2, " ").collect::<Vec<&str>>();
-                let goto_op_code = LabelOpCode { label: parts[1] };
-                instructions.push(OpCode::Goto(goto_op_code));
-                continue;
-            }
+    fn parse_instruction<'a>(tokens: &[Token<'a>]) -> Result<OpCode<'a>, LocatedError<'a>> {
+        let (_, command) = tokens[0];
 
-            if instruction.starts_with("if-goto") {
-                let parts = instruction.splitn(2, " ").collect::<Vec<&str>>();
-                let if_op_code = LabelOpCode { label: parts[1] };
-                instructions.push(OpCode::If(if_op_code));
-                continue;
+        let op_code = match command {
+            "push" | "pop" => {
+                let name = Self::operand(tokens, 1, "segment")?;
+                let segment = Segment::from_name(name)
+                    .ok_or_else(|| (tokens[1], DiagnosticKind::UnknownSegment(name.to_owned())))?;
+                let offset = Self::number(tokens, 2, "index", u32::MAX)?;
+                let op_code = SegmentOpCode { segment, offset };
+
+                if command == "push" {
+                    OpCode::Push(op_code)
+                } else {
+                    OpCode::Pop(op_code)
+                }
             }
+            "label" | "goto" | "if-goto" => {
+                let label = Self::symbol(tokens, 1, "label")?;
+                let op_code = LabelOpCode { label };
 
-            if instruction.starts_with("call") {
-                let parts = instruction.splitn(3, " ").collect::<Vec<&str>>();
-                let call_op_code = OpCode::Call {
-                    func_name: parts[1],
-                    num_args: parts[2].parse::<u8>().unwrap(),
-                };
-                instructions.push(call_op_code);
-                continue;
+                match command {
+                    "label" => OpCode::Label(op_code),
+                    "goto" => OpCode::Goto(op_code),
+                    _ => OpCode::If(op_code),
+                }
+            }
+            "call" => OpCode::Call {
+                func_name: Self::symbol(tokens, 1, "function name")?,
+                num_args: Self::number(tokens, 2, "argument count", u8::MAX as u32)? as u8,
+            },
+            "function" => OpCode::Function {
+                func_name: Self::symbol(tokens, 1, "function name")?,
+                num_locals: Self::number(tokens, 2, "local count", u8::MAX as u32)? as u8,
+            },
+            "add" => OpCode::Add,
+            "sub" => OpCode::Sub,
+            "neg" => OpCode::Neg,
+            "eq" => OpCode::Eq,
+            "gt" => OpCode::Gt,
+            "lt" => OpCode::Lt,
+            "and" => OpCode::And,
+            "or" => OpCode::Or,
+            "not" => OpCode::Not,
+            "return" => OpCode::Return,
+            _ => {
+                return Err((
+                    tokens[0],
+                    DiagnosticKind::UnknownCommand(command.to_owned()),
+                ))
             }
+        };
 
-            if instruction.starts_with("function") {
-                let parts = instruction.splitn(3, " ").collect::<Vec<&str>>();
-                let func_op_code = OpCode::Function {
-                    func_name: parts[1],
-                    num_locals: parts[2].parse::<u8>().unwrap(),
-                };
-                instructions.push(func_op_code);
-                continue;
+        // every command has a fixed number of operands, anything after them is an error
+        let arity = match op_code {
+            OpCode::Push(_) | OpCode::Pop(_) | OpCode::Call { .. } | OpCode::Function { .. } => 3,
+            OpCode::Label(_) | OpCode::Goto(_) | OpCode::If(_) => 2,
+            _ => 1,
+        };
+
+        if let Some(&extra) = tokens.get(arity) {
+            return Err((
+                extra,
+                DiagnosticKind::ExtraTokens {
+                    command: command.to_owned(),
+                    found: extra.1.to_owned(),
+                },
+            ));
+        }
+
+        Ok(op_code)
+    }
+
+    fn operand<'a>(
+        tokens: &[Token<'a>],
+        position: usize,
+        operand: &'static str,
+    ) -> Result<&'a str, LocatedError<'a>> {
+        match tokens.get(position) {
+            Some(&(_, value)) => Ok(value),
+            None => {
+                // point just past the last token of the instruction
+                let (offset, last) = tokens[tokens.len() - 1];
+                Err((
+                    (offset + last.len(), " "),
+                    DiagnosticKind::MissingOperand {
+                        command: tokens[0].1.to_owned(),
+                        operand,
+                    },
+                ))
             }
+        }
+    }
+
+    /// A label or function name. `$` is left out so the labels generated
+    /// as `Scope$$kind.N` can never collide with a `Scope$label`.
+    fn symbol<'a>(
+        tokens: &[Token<'a>],
+        position: usize,
+        operand: &'static str,
+    ) -> Result<&'a str, LocatedError<'a>> {
+        let value = Self::operand(tokens, position, operand)?;
+
+        if !is_symbol(value) {
+            return Err((
+                tokens[position],
+                DiagnosticKind::InvalidSymbol {
+                    operand,
+                    found: value.to_owned(),
+                },
+            ));
+        }
+
+        Ok(value)
+    }
+
+    fn number<'a>(
+        tokens: &[Token<'a>],
+        position: usize,
+        operand: &'static str,
+        max: u32,
+    ) -> Result<u32, LocatedError<'a>> {
+        let value = Self::operand(tokens, position, operand)?;
+        let token = tokens[position];
 
-            let op_code = match instruction {
-                "add" => OpCode::Add,
-                "sub" => OpCode::Sub,
-                "neg" => OpCode::Neg,
-                "eq" => OpCode::Eq,
-                "gt" => OpCode::Gt,
-                "lt" => OpCode::Lt,
-                "and" => OpCode::And,
-                "or" => OpCode::Or,
-                "not" => OpCode::Not,
-                "return" => OpCode::Return,
-                _ => {
-                    println!("Invalid instruction >>> `{}`", instruction);
-                    panic!("Invalid instruction")
+        if !value.chars().all(|c| c.is_ascii_digit()) {
+            return Err((
+                token,
+                DiagnosticKind::NonNumericIndex {
+                    operand,
+                    found: value.to_owned(),
+                },
+            ));
+        }
+
+        match value.parse::<u32>() {
+            Ok(number) if number <= max => Ok(number),
+            _ => Err((
+                token,
+                DiagnosticKind::CountOutOfRange {
+                    operand,
+                    found: value.to_owned(),
+                    max,
+                },
+            )),
+        }
+    }
+
+    pub(crate) fn tokenize(line: &str) -> Vec<Token<'_>> {
+        // strip comments
+        let code = match line.find("//") {
+            Some(index) => &line[..index],
+            None => line,
+        };
+
+        let mut tokens = Vec::new();
+        let mut start = None;
+
+        for (index, c) in code.char_indices() {
+            if c.is_whitespace() {
+                if let Some(start) = start.take() {
+                    tokens.push((start, &code[start..index]));
                 }
-            };
+            } else if start.is_none() {
+                start = Some(index);
+            }
+        }
 
-            instructions.push(op_code)
+        if let Some(start) = start {
+            tokens.push((start, &code[start..]));
         }
 
-        instructions
+        tokens
     }
 }
+
+/// VM symbols are letters, digits, `_`, `.` and `:`, not starting with a digit
+pub(crate) fn is_symbol(name: &str) -> bool {
+    let legal = |c: char| c.is_ascii_alphanumeric() || "_.:".contains(c);
+    name.chars().all(legal) && name.starts_with(|c: char| !c.is_ascii_digit())
+}
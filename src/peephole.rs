@@ -0,0 +1,197 @@
+use crate::code_writer::scratch;
+
+/// A line of generated assembly, as buffered by `CodeWriter`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Line {
+    Instruction(String),
+    /// a `(LABEL)` declaration, including the parentheses
+    Label(String),
+    Comment(String),
+}
+
+impl Line {
+    pub fn is_instruction(&self) -> bool {
+        matches!(self, Line::Instruction(_))
+    }
+}
+
+const PUSH_D: [&str; 5] = ["@SP", "A=M", "M=D", "@SP", "M=M+1"];
+const POP_TO_R13: [&str; 5] = [scratch::POP_ADDRESS, "M=D", "@SP", "AM=M-1", "D=M"];
+const STORE_AT_R13: [&str; 3] = [scratch::POP_ADDRESS, "A=M", "M=D"];
+const SEGMENT_BASES: [&str; 4] = ["@LCL", "@ARG", "@THIS", "@THAT"];
+
+/// Rewrites generated assembly in place without changing what it computes.
+///
+/// `-O1` removes dead stores and redundant A-register loads, `-O2` also
+/// fuses a push straight into the pop that follows it so the value never
+/// goes through the stack.
+pub fn optimize(lines: &mut Vec<Line>, level: u8) {
+    if level >= 2 {
+        fuse_push_pop(lines);
+    }
+    if level >= 1 {
+        remove_dead_stores(lines);
+        remove_redundant_loads(lines);
+    }
+}
+
+/// The next `len` instructions from `start` along with the index just past
+/// them, skipping comments. `None` if a label or the end comes first.
+fn instructions(lines: &[Line], start: usize, len: usize) -> Option<(Vec<&str>, usize)> {
+    let mut found = Vec::with_capacity(len);
+    let mut index = start;
+
+    while found.len() < len {
+        match lines.get(index)? {
+            Line::Instruction(instruction) => found.push(instruction.as_str()),
+            Line::Label(_) => return None,
+            Line::Comment(_) => {}
+        }
+        index += 1;
+    }
+
+    Some((found, index))
+}
+
+/// `push x; pop y` writes D to the stack only to read it straight back
+fn fuse_push_pop(lines: &mut Vec<Line>) {
+    let mut fused = Vec::with_capacity(lines.len());
+    let mut index = 0;
+
+    while index < lines.len() {
+        match fused_push_pop(lines, index) {
+            Some((end, replacement)) => {
+                // keep the comments of the code being replaced
+                let comments = lines[index..end]
+                    .iter()
+                    .filter(|line| matches!(line, Line::Comment(_)));
+                fused.extend(comments.cloned());
+                fused.extend(replacement.into_iter().map(Line::Instruction));
+                index = end;
+            }
+            None => {
+                fused.push(lines[index].clone());
+                index += 1;
+            }
+        }
+    }
+
+    *lines = fused;
+}
+
+/// A match of a push of D followed by a pop, as the index just past it and
+/// the instructions storing D straight into the popped address
+fn fused_push_pop(lines: &[Line], start: usize) -> Option<(usize, Vec<String>)> {
+    let (push, end) = instructions(lines, start, PUSH_D.len())?;
+    if push != PUSH_D || !lines[start].is_instruction() {
+        return None;
+    }
+
+    // the pop's address: `@addr D=A` or `@BASE D=M @k D=D+A`
+    let (address, end) = instructions(lines, end, 2)?;
+    let based = SEGMENT_BASES.contains(&address[0]) && address[1] == "D=M";
+    let (offset, end) = if based {
+        let (offset, end) = instructions(lines, end, 2)?;
+        if !offset[0].starts_with('@') || offset[1] != "D=D+A" {
+            return None;
+        }
+        (Some(offset[0]), end)
+    } else {
+        if !address[0].starts_with('@') || address[1] != "D=A" {
+            return None;
+        }
+        (None, end)
+    };
+
+    let (pop, mut end) = instructions(lines, end, POP_TO_R13.len())?;
+    if pop != POP_TO_R13 {
+        return None;
+    }
+    if let Some((clear, after)) = instructions(lines, end, 1) {
+        if clear == ["M=0"] {
+            end = after;
+        }
+    }
+    let (store, end) = instructions(lines, end, STORE_AT_R13.len())?;
+    if store != STORE_AT_R13 {
+        return None;
+    }
+
+    let replacement = match offset {
+        // hold the value while the address goes to R13
+        Some(offset) => vec![
+            scratch::FUSED_VALUE,
+            "M=D",
+            address[0],
+            "D=M",
+            offset,
+            "D=D+A",
+            scratch::POP_ADDRESS,
+            "M=D",
+            scratch::FUSED_VALUE,
+            "D=M",
+            scratch::POP_ADDRESS,
+            "A=M",
+            "M=D",
+        ],
+        None => vec![address[0], "M=D"],
+    };
+
+    Some((end, replacement.into_iter().map(str::to_owned).collect()))
+}
+
+/// Popping clears the slot it read from with `M=0`, but nothing reads
+/// above the stack pointer so the store is dead
+fn remove_dead_stores(lines: &mut Vec<Line>) {
+    let mut dead = vec![false; lines.len()];
+
+    for index in 0..lines.len() {
+        if let Some((pop, end)) = instructions(lines, index, 4) {
+            let reads_popped_value = pop[0] == "@SP"
+                && pop[1] == "AM=M-1"
+                && pop[2].starts_with("D=")
+                && pop[3] == "M=0";
+
+            if lines[index].is_instruction() && reads_popped_value {
+                // the match ends with the store, so `end - 1` is never a comment
+                dead[end - 1] = true;
+            }
+        }
+    }
+
+    let mut index = 0;
+    lines.retain(|_| {
+        index += 1;
+        !dead[index - 1]
+    });
+}
+
+/// Drops `@X` when the A register is already known to hold `X`
+fn remove_redundant_loads(lines: &mut Vec<Line>) {
+    let mut a_register: Option<String> = None;
+
+    lines.retain(|line| match line {
+        Line::Instruction(instruction) => {
+            if let Some(value) = instruction.strip_prefix('@') {
+                if a_register.as_deref() == Some(value) {
+                    return false;
+                }
+                a_register = Some(value.to_owned());
+                return true;
+            }
+
+            let (dest, rest) = instruction.split_once('=').unwrap_or(("", instruction));
+            let unconditional_jump = rest.ends_with(";JMP");
+            if dest.contains('A') || unconditional_jump {
+                a_register = None;
+            }
+            true
+        }
+        // control can arrive here from anywhere
+        Line::Label(_) => {
+            a_register = None;
+            true
+        }
+        Line::Comment(_) => true,
+    });
+}
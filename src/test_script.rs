@@ -0,0 +1,650 @@
+use crate::{
+    assembler::Program,
+    emulator::Emulator,
+    interpreter::Interpreter,
+    translator::{SourceFile, SourceOptions, Translator},
+};
+use anyhow::{bail, Context, Result};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+// guards against `while` conditions that never become false
+const MAX_STEPS: u64 = 50_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Variable {
+    /// `RAM[i]`, and the VM names for fixed addresses such as `sp` or `temp[i]`
+    Ram(u16),
+    /// `local[i]`, `argument[i]`, `this[i]` and `that[i]`, relative to the
+    /// address held in RAM[register]
+    Based {
+        register: u16,
+        index: u16,
+    },
+    A,
+    D,
+    Pc,
+    Time,
+}
+
+impl Variable {
+    fn parse(name: &str) -> Result<Self> {
+        let (base, index) = match name.split_once('[') {
+            Some((base, index)) => {
+                let index = index
+                    .strip_suffix(']')
+                    .and_then(|index| index.parse::<u16>().ok())
+                    .with_context(|| format!("invalid variable `{}`", name))?;
+                (base, Some(index))
+            }
+            None => (name, None),
+        };
+
+        let variable = match (base, index) {
+            ("RAM", Some(index)) => Self::Ram(index),
+            ("temp", Some(index)) => Self::Ram(5 + index),
+            ("pointer", Some(index)) => Self::Ram(3 + index),
+            ("local", Some(index)) => Self::Based { register: 1, index },
+            ("argument", Some(index)) => Self::Based { register: 2, index },
+            ("this", Some(index)) => Self::Based { register: 3, index },
+            ("that", Some(index)) => Self::Based { register: 4, index },
+            ("sp", None) => Self::Ram(0),
+            ("local", None) => Self::Ram(1),
+            ("argument", None) => Self::Ram(2),
+            ("this", None) => Self::Ram(3),
+            ("that", None) => Self::Ram(4),
+            ("A", None) => Self::A,
+            ("D", None) => Self::D,
+            ("PC", None) => Self::Pc,
+            ("time", None) => Self::Time,
+            _ => bail!("unknown variable `{}`", name),
+        };
+
+        Ok(variable)
+    }
+}
+
+/// A column of `output-list`, e.g. `RAM[256]%D2.6.2`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Column {
+    name: String,
+    variable: Variable,
+    format: char,
+    left: usize,
+    width: usize,
+    right: usize,
+}
+
+impl Column {
+    fn parse(spec: &str) -> Result<Self> {
+        let (name, format) = spec.split_once('%').unwrap_or((spec, "B1.16.1"));
+        let mut chars = format.chars();
+        let format_char = chars.next().unwrap_or('B');
+        let widths = chars
+            .as_str()
+            .split('.')
+            .map(|width| width.parse::<usize>())
+            .collect::<Result<Vec<_>, _>>()
+            .ok()
+            .filter(|widths| widths.len() == 3 && "BDXS".contains(format_char))
+            .with_context(|| format!("invalid output format `{}`", spec))?;
+
+        Ok(Self {
+            name: name.to_owned(),
+            variable: Variable::parse(name)?,
+            format: format_char,
+            left: widths[0],
+            width: widths[1],
+            right: widths[2],
+        })
+    }
+
+    fn header(&self) -> String {
+        let total = self.left + self.width + self.right;
+        let name: String = self.name.chars().take(total).collect();
+        let padding = total - name.len();
+
+        format!(
+            "{}{}{}",
+            " ".repeat(padding / 2),
+            name,
+            " ".repeat(padding - padding / 2)
+        )
+    }
+
+    fn cell(&self, value: i16) -> String {
+        let text = match self.format {
+            'X' => format!("{:04X}", value as u16),
+            'B' => format!("{:016b}", value as u16),
+            _ => value.to_string(),
+        };
+        let skip = text.len().saturating_sub(self.width);
+
+        format!(
+            "{}{:>width$}{}",
+            " ".repeat(self.left),
+            &text[skip..],
+            " ".repeat(self.right),
+            width = self.width
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Command {
+    Load(Option<String>),
+    OutputFile(String),
+    CompareTo(String),
+    OutputList(Vec<Column>),
+    Set(Variable, i16),
+    Output,
+    TickTock,
+    VmStep,
+    /// commands for the GUI tools, such as `echo`, that have no effect here
+    Ignored,
+    Repeat(Option<u64>, Vec<Command>),
+    While(Variable, Comparison, i16, Vec<Command>),
+}
+
+/// A row of the output that doesn't match the same row of the .cmp file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowMismatch {
+    /// 1-based line in the .cmp file
+    pub line: usize,
+    pub expected: String,
+    pub actual: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestReport {
+    /// every line written to the output file, header included
+    pub output: Vec<String>,
+    pub mismatches: Vec<RowMismatch>,
+}
+
+impl TestReport {
+    pub fn passed(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+enum Machine<'a> {
+    Cpu(Emulator),
+    Vm(Interpreter<'a>),
+}
+
+/// A nand2tetris .tst script, driving either the `Emulator` for .asm and
+/// .hack programs or the `Interpreter` for .vm programs.
+pub struct TestScript {
+    dir: PathBuf,
+    commands: Vec<Command>,
+}
+
+impl TestScript {
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("Error reading test script {}", path.display()))?;
+        let dir = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+
+        Self::parse(&source, dir)
+    }
+
+    /// Parses a script whose file names are relative to `dir`
+    pub fn parse(source: &str, dir: PathBuf) -> Result<Self> {
+        let tokens = Self::tokenize(source);
+        let mut position = 0;
+        let commands = Self::parse_commands(&tokens, &mut position)?;
+
+        if position < tokens.len() {
+            bail!("unexpected `{}` in test script", tokens[position]);
+        }
+
+        Ok(Self { dir, commands })
+    }
+
+    fn tokenize(source: &str) -> Vec<String> {
+        let mut tokens = Vec::new();
+        let mut chars = source.chars().peekable();
+        let mut word = String::new();
+
+        while let Some(c) = chars.next() {
+            let separator = match c {
+                '/' if chars.peek() == Some(&'/') => {
+                    for c in chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                    None
+                }
+                '/' if chars.peek() == Some(&'*') => {
+                    let mut previous = ' ';
+                    for c in chars.by_ref() {
+                        if previous == '*' && c == '/' {
+                            break;
+                        }
+                        previous = c;
+                    }
+                    None
+                }
+                '"' => {
+                    word.extend(chars.by_ref().take_while(|c| *c != '"'));
+                    continue;
+                }
+                ',' | ';' | '!' | '{' | '}' => Some(c),
+                c if c.is_whitespace() => None,
+                c => {
+                    word.push(c);
+                    continue;
+                }
+            };
+
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            if let Some(separator) = separator {
+                tokens.push(separator.to_string());
+            }
+        }
+
+        if !word.is_empty() {
+            tokens.push(word);
+        }
+
+        tokens
+    }
+
+    fn parse_commands(tokens: &[String], position: &mut usize) -> Result<Vec<Command>> {
+        let mut commands = Vec::new();
+
+        while let Some(token) = tokens.get(*position) {
+            if token == "}" {
+                break;
+            }
+
+            // arguments run up to the terminator or the start of a block
+            let start = *position + 1;
+            let mut end = start;
+            while end < tokens.len() && !",;!{}".contains(tokens[end].as_str()) {
+                end += 1;
+            }
+            let args = &tokens[start..end];
+            *position = end;
+
+            let command = match (token.as_str(), args) {
+                ("load", []) => Command::Load(None),
+                ("load", [file]) => Command::Load(Some(file.clone())),
+                ("output-file", [file]) => Command::OutputFile(file.clone()),
+                ("compare-to", [file]) => Command::CompareTo(file.clone()),
+                ("output-list", columns) => Command::OutputList(
+                    columns
+                        .iter()
+                        .map(|column| Column::parse(column))
+                        .collect::<Result<_>>()?,
+                ),
+                ("set", [variable, value]) => {
+                    Command::Set(Variable::parse(variable)?, Self::parse_value(value)?)
+                }
+                ("output", []) => Command::Output,
+                ("ticktock", []) | ("tick", []) => Command::TickTock,
+                ("vmstep", []) => Command::VmStep,
+                ("tock", []) | ("echo", _) | ("clear-echo", []) => Command::Ignored,
+                ("repeat", count) => {
+                    let count = match count {
+                        [] => None,
+                        [count] => Some(
+                            count
+                                .parse::<u64>()
+                                .with_context(|| format!("invalid repeat count `{}`", count))?,
+                        ),
+                        _ => bail!("invalid repeat command"),
+                    };
+                    Command::Repeat(count, Self::parse_block(tokens, position)?)
+                }
+                ("while", [variable, comparison, value]) => {
+                    let comparison = match comparison.as_str() {
+                        "=" => Comparison::Eq,
+                        "<>" => Comparison::Ne,
+                        "<" => Comparison::Lt,
+                        ">" => Comparison::Gt,
+                        "<=" => Comparison::Le,
+                        ">=" => Comparison::Ge,
+                        _ => bail!("invalid comparison `{}`", comparison),
+                    };
+                    Command::While(
+                        Variable::parse(variable)?,
+                        comparison,
+                        Self::parse_value(value)?,
+                        Self::parse_block(tokens, position)?,
+                    )
+                }
+                _ => bail!("invalid test script command `{} {}`", token, args.join(" ")),
+            };
+            commands.push(command);
+
+            // blocks don't need a terminator
+            if let Some(terminator) = tokens.get(*position) {
+                if ",;!".contains(terminator.as_str()) {
+                    *position += 1;
+                }
+            }
+        }
+
+        Ok(commands)
+    }
+
+    fn parse_block(tokens: &[String], position: &mut usize) -> Result<Vec<Command>> {
+        if tokens.get(*position).map(String::as_str) != Some("{") {
+            bail!("expected `{{` to start a block");
+        }
+        *position += 1;
+
+        let commands = Self::parse_commands(tokens, position)?;
+        if tokens.get(*position).map(String::as_str) != Some("}") {
+            bail!("unterminated block");
+        }
+        *position += 1;
+
+        Ok(commands)
+    }
+
+    /// Decimal, or `%D`, `%X` and `%B` prefixed values
+    fn parse_value(value: &str) -> Result<i16> {
+        let parsed = match value.get(..2) {
+            Some("%D") => value[2..].parse::<i16>().ok(),
+            Some("%X") => u16::from_str_radix(&value[2..], 16).ok().map(|v| v as i16),
+            Some("%B") => u16::from_str_radix(&value[2..], 2).ok().map(|v| v as i16),
+            _ => value.parse::<i16>().ok(),
+        };
+
+        parsed.with_context(|| format!("invalid value `{}`", value))
+    }
+
+    pub fn run(&self) -> Result<TestReport> {
+        // VM programs are parsed up front since the interpreter borrows from them
+        let mut vm_sources = HashMap::new();
+        self.load_vm_sources(&self.commands, &mut vm_sources)?;
+
+        let mut runner = Runner {
+            script: self,
+            vm_sources: &vm_sources,
+            machine: None,
+            columns: Vec::new(),
+            output: Vec::new(),
+            output_file: None,
+            compare: None,
+            mismatches: Vec::new(),
+            steps: 0,
+        };
+        runner.run(&self.commands)?;
+
+        if let Some(output_file) = &runner.output_file {
+            let mut contents = runner.output.join("\n");
+            contents.push('\n');
+            fs::write(output_file, contents)
+                .with_context(|| format!("Error writing {}", output_file.display()))?;
+        }
+
+        Ok(TestReport {
+            output: runner.output,
+            mismatches: runner.mismatches,
+        })
+    }
+
+    /// Reads every VM program a `load` names, including loads inside blocks
+    fn load_vm_sources(
+        &self,
+        commands: &[Command],
+        sources: &mut HashMap<Option<String>, Vec<SourceFile>>,
+    ) -> Result<()> {
+        for command in commands {
+            match command {
+                Command::Load(file)
+                    if self.is_vm_program(file.as_deref()) && !sources.contains_key(file) =>
+                {
+                    let path = self.path(file.as_deref().unwrap_or(""));
+                    let path = path
+                        .to_str()
+                        .with_context(|| format!("{} is not valid UTF-8", path.display()))?;
+                    let loaded = Translator::load_sources(&[path], &SourceOptions::default())?;
+                    sources.insert(file.clone(), loaded);
+                }
+                Command::Repeat(_, block) | Command::While(.., block) => {
+                    self.load_vm_sources(block, sources)?
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn path(&self, file: &str) -> PathBuf {
+        self.dir.join(file)
+    }
+
+    fn is_vm_program(&self, file: Option<&str>) -> bool {
+        match file {
+            Some(file) => !(file.ends_with(".asm") || file.ends_with(".hack")),
+            None => true,
+        }
+    }
+}
+
+struct Runner<'a, 'b> {
+    script: &'b TestScript,
+    /// by the file each `load` names
+    vm_sources: &'a HashMap<Option<String>, Vec<SourceFile>>,
+    machine: Option<Machine<'a>>,
+    columns: Vec<Column>,
+    output: Vec<String>,
+    output_file: Option<PathBuf>,
+    compare: Option<Vec<String>>,
+    mismatches: Vec<RowMismatch>,
+    steps: u64,
+}
+
+impl<'a, 'b> Runner<'a, 'b> {
+    fn run(&mut self, commands: &[Command]) -> Result<()> {
+        for command in commands {
+            match command {
+                Command::Load(file) => self.load(file.as_deref())?,
+                Command::OutputFile(file) => self.output_file = Some(self.script.path(file)),
+                Command::CompareTo(file) => {
+                    let path = self.script.path(file);
+                    let contents = fs::read_to_string(&path)
+                        .with_context(|| format!("Error reading {}", path.display()))?;
+                    self.compare = Some(contents.lines().map(str::to_owned).collect());
+                }
+                Command::OutputList(columns) => {
+                    self.columns = columns.clone();
+                    let header = columns.iter().map(Column::header).collect::<Vec<_>>();
+                    self.write_row(format!("|{}|", header.join("|")));
+                }
+                Command::Set(variable, value) => self.set(*variable, *value)?,
+                Command::Output => {
+                    let cells = self
+                        .columns
+                        .iter()
+                        .map(|column| Ok(column.cell(self.get(column.variable)?)))
+                        .collect::<Result<Vec<_>>>()?;
+                    self.write_row(format!("|{}|", cells.join("|")));
+                }
+                Command::TickTock => match self.machine_mut()? {
+                    Machine::Cpu(emulator) => emulator.step(),
+                    Machine::Vm(_) => bail!("`ticktock` needs a .asm or .hack program"),
+                },
+                Command::VmStep => match self.machine_mut()? {
+                    Machine::Vm(interpreter) => interpreter.step()?,
+                    Machine::Cpu(_) => bail!("`vmstep` needs a .vm program"),
+                },
+                Command::Ignored => {}
+                Command::Repeat(count, block) => {
+                    let mut iteration = 0;
+                    while count.is_none_or(|count| iteration < count) {
+                        self.run(block)?;
+                        iteration += 1;
+                        self.count_step()?;
+                    }
+                }
+                Command::While(variable, comparison, value, block) => {
+                    while Self::compare(self.get(*variable)?, *comparison, *value) {
+                        self.run(block)?;
+                        self.count_step()?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn load(&mut self, file: Option<&str>) -> Result<()> {
+        if !self.script.is_vm_program(file) {
+            let file = file.unwrap();
+            let path = self.script.path(file);
+            let source = fs::read_to_string(&path)
+                .with_context(|| format!("Error reading {}", path.display()))?;
+            let program = if file.ends_with(".hack") {
+                Program::from_hack(&source)?
+            } else {
+                Program::assemble(&source)?
+            };
+
+            self.machine = Some(Machine::Cpu(Emulator::new(program)));
+            return Ok(());
+        }
+
+        let sources = self
+            .vm_sources
+            .get(&file.map(str::to_owned))
+            .with_context(|| format!("VM program `{}` was not read", file.unwrap_or(".")))?;
+        let (files, diagnostics) = Translator::parse_sources(sources);
+        if diagnostics.has_errors() {
+            return Err(diagnostics.into());
+        }
+
+        let files = files
+            .into_iter()
+            .map(|(namespace, instructions)| {
+                let op_codes = instructions.into_iter().map(|i| i.op_code).collect();
+                (namespace, op_codes)
+            })
+            .collect();
+        let mut interpreter = Interpreter::new(files)?;
+
+        // like the course's VM emulator, start in Sys.init when there is one
+        if interpreter.has_function("Sys.init") {
+            interpreter.start_at("Sys.init")?;
+        }
+
+        self.machine = Some(Machine::Vm(interpreter));
+        Ok(())
+    }
+
+    fn machine_mut(&mut self) -> Result<&mut Machine<'a>> {
+        self.machine
+            .as_mut()
+            .context("no program loaded, the script needs a `load` command first")
+    }
+
+    fn machine(&self) -> Result<&Machine<'a>> {
+        self.machine
+            .as_ref()
+            .context("no program loaded, the script needs a `load` command first")
+    }
+
+    fn count_step(&mut self) -> Result<()> {
+        self.steps += 1;
+        if self.steps > MAX_STEPS {
+            bail!("test script ran for more than {} iterations", MAX_STEPS);
+        }
+
+        Ok(())
+    }
+
+    fn ram(&self, address: u16) -> Result<i16> {
+        Ok(match self.machine()? {
+            Machine::Cpu(emulator) => emulator.ram(address),
+            Machine::Vm(interpreter) => interpreter.ram(address),
+        })
+    }
+
+    fn get(&self, variable: Variable) -> Result<i16> {
+        let value = match (variable, self.machine()?) {
+            (Variable::Ram(address), _) => self.ram(address)?,
+            (Variable::Based { register, index }, _) => {
+                self.ram((self.ram(register)? as u16).wrapping_add(index))?
+            }
+            (Variable::A, Machine::Cpu(emulator)) => emulator.a(),
+            (Variable::D, Machine::Cpu(emulator)) => emulator.d(),
+            (Variable::Pc, Machine::Cpu(emulator)) => emulator.pc() as i16,
+            (Variable::Time, Machine::Cpu(emulator)) => emulator.cycles() as i16,
+            (Variable::Time, Machine::Vm(interpreter)) => interpreter.steps() as i16,
+            (_, Machine::Vm(_)) => bail!("the VM has no A, D or PC registers"),
+        };
+
+        Ok(value)
+    }
+
+    fn set(&mut self, variable: Variable, value: i16) -> Result<()> {
+        let address = match variable {
+            Variable::Ram(address) => address,
+            Variable::Based { register, index } => (self.ram(register)? as u16).wrapping_add(index),
+            _ => bail!("only RAM and segment variables can be set"),
+        };
+
+        match self.machine_mut()? {
+            Machine::Cpu(emulator) => emulator.set_ram(address, value),
+            Machine::Vm(interpreter) => interpreter.set_ram(address, value),
+        }
+
+        Ok(())
+    }
+
+    fn compare(left: i16, comparison: Comparison, right: i16) -> bool {
+        match comparison {
+            Comparison::Eq => left == right,
+            Comparison::Ne => left != right,
+            Comparison::Lt => left < right,
+            Comparison::Gt => left > right,
+            Comparison::Le => left <= right,
+            Comparison::Ge => left >= right,
+        }
+    }
+
+    /// Appends an output row, checking it against the same row of the .cmp
+    /// file cell by cell so differences in padding don't count
+    fn write_row(&mut self, row: String) {
+        if let Some(compare) = &self.compare {
+            let line = self.output.len();
+            let expected = compare.get(line).map(String::as_str).unwrap_or("");
+            let cells = |row: &str| -> Vec<String> {
+                row.split('|').map(|cell| cell.trim().to_owned()).collect()
+            };
+
+            if cells(expected) != cells(&row) {
+                self.mismatches.push(RowMismatch {
+                    line: line + 1,
+                    expected: expected.to_owned(),
+                    actual: row.clone(),
+                });
+            }
+        }
+
+        self.output.push(row);
+    }
+}
@@ -1,98 +1,608 @@
-use crate::{code_writer::CodeWriter, op_code::OpCode, parser::Parser};
-use anyhow::{Context, Ok, Result};
-use std::{fs::File, io::BufWriter, path::Path};
+use crate::{
+    assembler::Program,
+    bootstrap::Bootstrap,
+    call_graph::CallGraph,
+    code_writer::CodeWriter,
+    diagnostic::Diagnostics,
+    linker::Linker,
+    op_code::{Instruction, OpCode},
+    parser::{self, Parser},
+    validator::Validator,
+    vm_optimizer,
+};
+use anyhow::{bail, Context, Ok, Result};
+use glob::{MatchOptions, Pattern};
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsStr,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+};
+
+/// `*` and `?` stay within one path component, `**` crosses them
+const GLOB_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// An input or output path standing for standard input or output
+pub const STDIO: &str = "-";
+
+/// The static namespace of a file read from standard input
+const STDIN_NAMESPACE: &str = "Stdin";
+
+/// The kind of file written to the output path
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Emit {
+    /// Hack assembly text
+    #[default]
+    Asm,
+    /// Hack machine code, one binary word per line
+    Hack,
+}
+
+impl Emit {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Emit::Asm => "asm",
+            Emit::Hack => "hack",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TranslatorOptions {
+    pub bootstrap: Option<Bootstrap>,
+    pub emit: Emit,
+    /// jump to shared `$$CALL` and `$$RETURN` routines instead of inlining them
+    pub shared_routines: bool,
+    /// optimisation level from 0 to 2, folding VM op codes and running the
+    /// peephole optimizer over the assembly from level 1
+    pub opt_level: u8,
+    /// drop functions the entry function can never reach
+    pub strip_unused: bool,
+    /// entry function `strip_unused` keeps without bootstrap code to call one,
+    /// `Sys.init` when it is defined if unset
+    pub entry: Option<String>,
+    /// functions kept by `strip_unused` even when nothing calls them
+    pub keep: Vec<String>,
+    pub sources: SourceOptions,
+}
+
+/// How input files are found and named
+#[derive(Debug, Clone, Default)]
+pub struct SourceOptions {
+    /// prefix each static namespace with the name of the file's directory,
+    /// so `lib/Math.vm` uses `@lib.Math.i`
+    pub prefix_namespaces: bool,
+    /// also look for .vm files in subdirectories of an input directory
+    pub recursive: bool,
+    /// globs a file found in a directory must match one of, if any are given
+    pub include: Vec<String>,
+    /// globs excluding files found in a directory
+    pub exclude: Vec<String>,
+    /// function whose file is ordered before the others, found by its class
+    /// name so `Sys.init` puts `Sys.vm` first
+    pub entry: Option<String>,
+    /// paths searched for the functions the inputs call but don't define,
+    /// with only the files defining them translated
+    pub libraries: Vec<String>,
+}
+
+/// The result of a successful translation
+#[derive(Debug)]
+pub struct Translation {
+    /// size of the generated program in ROM words
+    pub instruction_count: u32,
+    /// size the program would have had without any of the size optimisations,
+    /// when one was enabled
+    pub baseline_instruction_count: Option<u32>,
+    /// functions dropped as unreachable, in program order
+    pub removed_functions: Vec<String>,
+}
+
+/// A .vm input file and the static namespace its code is translated under
+pub struct SourceFile {
+    pub namespace: String,
+    pub parser: Parser,
+}
+
+/// The instructions of one file, under its static namespace
+pub type SourceInstructions<'a> = (String, Vec<Instruction<'a>>);
 
 pub struct Translator {
-    input_filepath: String,
+    inputs: Vec<String>,
     output_filepath: String,
-    bootstrap: bool,
+    options: TranslatorOptions,
 }
 
 impl Translator {
-    pub fn new(input_filepath: String, output_filepath: String, bootstrap: bool) -> Self {
+    pub fn new(
+        input_filepath: String,
+        output_filepath: String,
+        options: TranslatorOptions,
+    ) -> Self {
         Self {
-            input_filepath,
+            inputs: vec![input_filepath],
             output_filepath,
-            bootstrap,
+            options,
         }
     }
 
-    pub fn translate(&mut self) -> Result<()> {
-        let input_files = self.get_path_files(&self.input_filepath);
+    /// Translates these paths along with the first input, as one program
+    pub fn with_inputs(mut self, inputs: impl IntoIterator<Item = String>) -> Self {
+        self.inputs.extend(inputs);
+        self
+    }
 
-        let output_file =
-            File::create(self.output_filepath.as_str()).context("Error creating output file")?;
-        let mut writer = BufWriter::new(output_file);
-        let mut code_writer = CodeWriter::new(&mut writer, self.bootstrap);
+    /// Translates every input file. If any file had errors, all diagnostics
+    /// are returned as the error instead.
+    pub fn translate(&mut self) -> Result<Translation> {
+        let inputs: Vec<&str> = self.inputs.iter().map(String::as_str).collect();
+        let sources = Self::load_sources(&inputs, &self.options.sources)?;
+        let (files, diagnostics) = Self::parse_sources(&sources);
 
-        for file in input_files {
-            let file = Path::new(&file);
-            code_writer.set_current_filename(
-                &file
-                    .file_name()
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .replace(".vm", ""),
-            );
-            let input_file = File::open(file).unwrap();
-            let parser = Parser::new(&input_file);
-
-            let op_codes = parser.parse();
-            for op_code in op_codes {
-                code_writer.comment(&format!("{}", op_code.to_string()));
-
-                match op_code {
-                    OpCode::Add
-                    | OpCode::Sub
-                    | OpCode::Neg
-                    | OpCode::Eq
-                    | OpCode::Gt
-                    | OpCode::Lt
-                    | OpCode::And
-                    | OpCode::Or
-                    | OpCode::Not => code_writer.write_arithmetic(&op_code),
-                    OpCode::Push(push_op_code) => code_writer.write_push(&push_op_code),
-                    OpCode::Pop(pop_op_code) => code_writer.write_pop(&pop_op_code),
-                    OpCode::Label(op_code) => code_writer.write_label(&op_code.label),
-                    OpCode::Goto(op_code) => code_writer.write_goto(&op_code),
-                    OpCode::If(op_code) => code_writer.write_if(&op_code),
-                    OpCode::Call {
-                        func_name,
-                        num_args,
-                    } => code_writer.write_call(func_name, num_args),
-                    OpCode::Function {
-                        func_name,
-                        num_locals,
-                    } => code_writer.write_function(func_name, num_locals),
-                    OpCode::Return => code_writer.write_return(),
-                };
+        if diagnostics.has_errors() {
+            return Err(diagnostics.into());
+        }
+
+        let options = &self.options;
+        let baseline_instruction_count =
+            if options.shared_routines || options.opt_level > 0 || options.strip_unused {
+                Some(self.write_asm(&files, false, 0)?.1)
+            } else {
+                None
+            };
+
+        let (files, removed_functions) = if options.strip_unused {
+            self.strip_unused(&sources, files)?
+        } else {
+            (files, Vec::new())
+        };
+        let (asm, instruction_count) =
+            self.write_asm(&files, options.shared_routines, options.opt_level)?;
+
+        let output = match self.options.emit {
+            Emit::Asm => asm,
+            Emit::Hack => {
+                let asm = String::from_utf8(asm).context("Generated assembly is not UTF-8")?;
+                Program::assemble(&asm)
+                    .context("Error assembling generated code")?
+                    .to_hack()
+                    .into_bytes()
+            }
+        };
+
+        if self.output_filepath == STDIO {
+            io::stdout()
+                .write_all(&output)
+                .context("Error writing output")?;
+        } else {
+            Self::write_output(Path::new(&self.output_filepath), &output)?;
+        }
+
+        Ok(Translation {
+            instruction_count,
+            baseline_instruction_count,
+            removed_functions,
+        })
+    }
+
+    /// The course's output path for an input: `Dir/Dir.asm` for a directory,
+    /// `File.asm` next to a file, and standard output for standard input
+    pub fn default_output(input: &str, emit: Emit) -> Result<String> {
+        if input == STDIO {
+            return Ok(STDIO.to_owned());
+        }
+
+        let path = Path::new(input);
+        let output = if path.is_dir() {
+            // `.` and `..` have no name of their own
+            let dir = path
+                .canonicalize()
+                .with_context(|| format!("Error resolving {}", path.display()))?;
+            let name = dir
+                .file_name()
+                .with_context(|| format!("{} has no name to call the output", path.display()))?;
+            let name = name
+                .to_str()
+                .with_context(|| format!("{} is not valid UTF-8", dir.display()))?;
+            path.join(format!("{}.{}", name, emit.extension()))
+        } else {
+            path.with_extension(emit.extension())
+        };
+
+        let output = output
+            .to_str()
+            .with_context(|| format!("{} is not valid UTF-8", output.display()))?;
+        Ok(output.to_owned())
+    }
+
+    /// Writes to a temporary file next to `path` and renames it into place,
+    /// so a failed write never leaves `path` truncated
+    fn write_output(path: &Path, output: &[u8]) -> Result<()> {
+        let name = path
+            .file_name()
+            .with_context(|| format!("{} is not a file path", path.display()))?;
+        let temp =
+            path.with_file_name(format!(".{}.{}.tmp", name.to_string_lossy(), process::id()));
+
+        let result = fs::write(&temp, output)
+            .context("Error writing output file")
+            .and_then(|_| fs::rename(&temp, path).context("Error replacing output file"));
+        if result.is_err() {
+            let _ = fs::remove_file(&temp);
+        }
+
+        result
+    }
+
+    /// Drops every function that neither the entry function, the `keep`
+    /// list nor top-level code can reach, returning the names dropped
+    fn strip_unused<'a>(
+        &self,
+        sources: &[SourceFile],
+        files: Vec<SourceInstructions<'a>>,
+    ) -> Result<(Vec<SourceInstructions<'a>>, Vec<String>)> {
+        let graph = Self::call_graph(sources, &files);
+
+        // a program without bootstrap code may have no entry, as long as
+        // something is kept
+        let entry = match (&self.options.bootstrap, &self.options.entry) {
+            (Some(bootstrap), _) => Some(bootstrap.entry.as_str()),
+            (None, Some(entry)) => Some(entry.as_str()),
+            (None, None) => Some("Sys.init").filter(|entry| graph.function(entry).is_some()),
+        };
+        let mut roots: Vec<&str> = entry.into_iter().collect();
+        roots.extend(self.options.keep.iter().map(String::as_str));
+        if roots.is_empty() {
+            bail!("cannot strip unused functions, `Sys.init` is not defined and no entry or kept function was given");
+        }
+        for root in &roots {
+            if graph.function(root).is_none() {
+                bail!("cannot strip unused functions, `{}` is not defined", root);
+            }
+        }
+        let reachable = graph.reachable(&roots);
+
+        let mut removed = Vec::new();
+        let files = files
+            .into_iter()
+            .map(|(namespace, instructions)| {
+                // code before the first function always stays
+                let mut keep = true;
+                let instructions = instructions
+                    .into_iter()
+                    .filter(|instruction| {
+                        if let OpCode::Function { func_name, .. } = instruction.op_code {
+                            keep = reachable.contains(func_name);
+                            if !keep {
+                                removed.push(func_name.to_owned());
+                            }
+                        }
+                        keep
+                    })
+                    .collect();
+
+                (namespace, instructions)
+            })
+            .collect();
+
+        Ok((files, removed))
+    }
+
+    /// Writes the assembly for every file, along with its instruction count
+    fn write_asm(
+        &self,
+        files: &[SourceInstructions],
+        shared_routines: bool,
+        opt_level: u8,
+    ) -> Result<(Vec<u8>, u32)> {
+        let mut asm = Vec::new();
+        let mut code_writer =
+            CodeWriter::new(&mut asm, self.options.bootstrap.as_ref()).with_opt_level(opt_level);
+        if shared_routines {
+            code_writer = code_writer.with_shared_routines();
+        }
+
+        for (namespace, instructions) in files {
+            code_writer.set_current_filename(namespace);
+
+            let mut op_codes: Vec<_> = instructions.iter().map(|i| i.op_code.clone()).collect();
+            if opt_level > 0 {
+                op_codes = vm_optimizer::optimize(&op_codes);
+            }
+
+            for op_code in &op_codes {
+                code_writer.write_op_code(op_code)?;
             }
         }
 
         code_writer
             .flush()
             .context("Error flushing writer contents")?;
+        let instruction_count = code_writer.instruction_count();
 
-        Ok(())
+        Ok((asm, instruction_count))
     }
 
-    fn get_path_files(&self, raw_path: &str) -> Vec<String> {
+    /// Reads every .vm file under the input paths, ready for parsing, along
+    /// with the library files needed to define every function they call.
+    /// Fails if a file's static namespace isn't a legal Hack symbol or is
+    /// shared with another file.
+    pub fn load_sources(inputs: &[&str], options: &SourceOptions) -> Result<Vec<SourceFile>> {
+        let mut sources = Vec::new();
+        for input in inputs {
+            sources.extend(Self::load_files(input, options)?);
+        }
+        if !options.libraries.is_empty() {
+            sources.extend(Self::load_libraries(&sources, options)?);
+        }
+
+        let mut namespaces: HashMap<&str, &str> = HashMap::new();
+        for source in &sources {
+            let filename = source.parser.filename();
+            if let Some(previous) = namespaces.insert(&source.namespace, filename) {
+                bail!(
+                    "{} and {} would share the static namespace `{}`",
+                    previous,
+                    filename,
+                    source.namespace
+                );
+            }
+        }
+
+        Ok(sources)
+    }
+
+    fn load_files(raw_path: &str, options: &SourceOptions) -> Result<Vec<SourceFile>> {
+        if raw_path == STDIO {
+            return Ok(vec![SourceFile {
+                namespace: STDIN_NAMESPACE.to_owned(),
+                parser: Parser::new("<stdin>", io::stdin()),
+            }]);
+        }
+
+        let mut sources = Vec::new();
+
+        for file in Self::get_path_files(raw_path, options)? {
+            let file = file.as_path();
+            let input_file =
+                File::open(file).with_context(|| format!("Error opening {}", file.display()))?;
+
+            sources.push(SourceFile {
+                namespace: Self::namespace(file, options)?,
+                parser: Parser::new(&file.display().to_string(), &input_file),
+            });
+        }
+
+        Ok(sources)
+    }
+
+    /// The library files defining the functions `sources` call but don't
+    /// define, and in turn the ones those call, like a static linker pulling
+    /// objects out of an archive. Libraries are searched in order and the
+    /// first file defining a function wins. Calls no library resolves are
+    /// left for the linker to report.
+    fn load_libraries(sources: &[SourceFile], options: &SourceOptions) -> Result<Vec<SourceFile>> {
+        let library_options = SourceOptions {
+            prefix_namespaces: options.prefix_namespaces,
+            recursive: options.recursive,
+            ..SourceOptions::default()
+        };
+        let mut library = Vec::new();
+        for dir in &options.libraries {
+            library.extend(Self::load_files(dir, &library_options)?);
+        }
+        let symbols: Vec<_> = library.iter().map(Self::symbols).collect();
+
+        let mut defined = HashSet::new();
+        let mut pending = Vec::new();
+        for source in sources {
+            let (functions, calls) = Self::symbols(source);
+            defined.extend(functions);
+            pending.extend(calls);
+        }
+
+        let mut needed = vec![false; library.len()];
+        while let Some(callee) = pending.pop() {
+            if defined.contains(&callee) {
+                continue;
+            }
+            let Some(index) = symbols
+                .iter()
+                .position(|(functions, _)| functions.contains(&callee))
+            else {
+                continue;
+            };
+
+            needed[index] = true;
+            defined.extend(symbols[index].0.iter().cloned());
+            pending.extend(symbols[index].1.iter().cloned());
+        }
+
+        Ok(library
+            .into_iter()
+            .zip(needed)
+            .filter_map(|(source, needed)| needed.then_some(source))
+            .collect())
+    }
+
+    /// The functions a source defines and the functions it calls
+    fn symbols(source: &SourceFile) -> (Vec<String>, Vec<String>) {
+        let (instructions, _) = source.parser.parse();
+        let mut graph = CallGraph::new();
+        graph.add_file(source.parser.filename(), &source.namespace, &instructions);
+
+        let functions = graph.functions().iter().map(|f| f.name.clone()).collect();
+        let calls = graph
+            .functions()
+            .iter()
+            .flat_map(|f| &f.calls)
+            .chain(graph.top_level_calls())
+            .map(|call| call.callee.clone())
+            .collect();
+
+        (functions, calls)
+    }
+
+    /// Parses and validates every source, then links them as one program,
+    /// returning the instructions of each file under its static namespace
+    /// along with all diagnostics, in file then line order.
+    pub fn parse_sources(sources: &[SourceFile]) -> (Vec<SourceInstructions<'_>>, Diagnostics) {
+        let (files, mut diagnostics) = Self::parse_files(sources);
+
+        let mut linker = Linker::new();
+        for (source, (_, instructions)) in sources.iter().zip(&files) {
+            linker.add_file(source.parser.filename(), instructions);
+        }
+        diagnostics.extend(linker.link());
+        Self::sort_diagnostics(sources, &mut diagnostics);
+
+        (files, diagnostics)
+    }
+
+    /// Like `parse_sources`, but checks each file on its own without linking
+    /// them, for tools that don't need a complete program
+    pub fn parse_files(sources: &[SourceFile]) -> (Vec<SourceInstructions<'_>>, Diagnostics) {
+        let mut diagnostics = Diagnostics::new();
+        let mut files = Vec::new();
+
+        for source in sources {
+            let (instructions, file_diagnostics) = source.parser.parse();
+            diagnostics.extend(file_diagnostics);
+            diagnostics.extend(Validator::new(source.parser.filename()).validate(&instructions));
+
+            files.push((source.namespace.clone(), instructions));
+        }
+        Self::sort_diagnostics(sources, &mut diagnostics);
+
+        (files, diagnostics)
+    }
+
+    /// Each check reports separately, so merge them back into file order
+    fn sort_diagnostics(sources: &[SourceFile], diagnostics: &mut Diagnostics) {
+        let file_order: HashMap<&str, usize> = sources
+            .iter()
+            .enumerate()
+            .map(|(index, source)| (source.parser.filename(), index))
+            .collect();
+        diagnostics.sort_by_key(|d| (file_order[d.filename.as_str()], d.line, d.column));
+    }
+
+    /// The call graph of the files `parse_sources` returned for `sources`
+    pub fn call_graph(sources: &[SourceFile], files: &[SourceInstructions]) -> CallGraph {
+        let mut graph = CallGraph::new();
+        for (source, (namespace, instructions)) in sources.iter().zip(files) {
+            graph.add_file(source.parser.filename(), namespace, instructions);
+        }
+
+        graph
+    }
+
+    /// The prefix of the `@File.i` symbols used for a file's static segment
+    fn namespace(file: &Path, options: &SourceOptions) -> Result<String> {
+        let stem = file
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .with_context(|| format!("{} has no usable file name", file.display()))?;
+
+        let namespace = if options.prefix_namespaces {
+            let path = file
+                .canonicalize()
+                .with_context(|| format!("Error resolving {}", file.display()))?;
+            let dir = path
+                .parent()
+                .and_then(|dir| dir.file_name())
+                .and_then(|dir| dir.to_str())
+                .with_context(|| format!("{} has no directory to prefix", file.display()))?;
+            format!("{}.{}", dir, stem)
+        } else {
+            stem.to_owned()
+        };
+
+        if !parser::is_symbol(&namespace) {
+            bail!(
+                "{} can't be translated, its static namespace `{}` is not a legal VM symbol",
+                file.display(),
+                namespace
+            );
+        }
+
+        Ok(namespace)
+    }
+
+    /// The input file, or the .vm files in the input directory that pass the
+    /// include and exclude globs, sorted by path with the entry file first
+    fn get_path_files(raw_path: &str, options: &SourceOptions) -> Result<Vec<PathBuf>> {
         let path = Path::new(raw_path);
         if path.is_file() {
-            vec![path.to_str().unwrap().to_owned()]
-        } else {
-            let mut files = vec![];
+            return Ok(vec![path.to_owned()]);
+        }
+
+        let include = Self::patterns(&options.include)?;
+        let exclude = Self::patterns(&options.exclude)?;
+        let matches = |patterns: &[Pattern], file: &Path| {
+            patterns.iter().any(|pattern| {
+                // globs without a separator match the name alone
+                let target = if pattern.as_str().contains('/') {
+                    file
+                } else {
+                    Path::new(file.file_name().unwrap())
+                };
+                pattern.matches_path_with(target, GLOB_OPTIONS)
+            })
+        };
+
+        let mut files = Vec::new();
+        let mut dirs = vec![path.to_owned()];
+        while let Some(dir) = dirs.pop() {
+            let entries = dir
+                .read_dir()
+                .with_context(|| format!("Error reading directory {}", dir.display()))?;
 
-            for entry in path.read_dir().expect("unable to read directory") {
-                if let std::result::Result::Ok(entry) = entry {
-                    if entry.path().to_str().unwrap().ends_with("vm") {
-                        files.push(entry.path().to_str().unwrap().to_owned())
+            for entry in entries.flatten() {
+                let entry = entry.path();
+                if entry.is_dir() {
+                    if options.recursive {
+                        dirs.push(entry);
                     }
+                    continue;
                 }
-            }
 
-            files
+                let relative = entry.strip_prefix(path).unwrap();
+                let is_vm = entry.extension().is_some_and(|extension| extension == "vm");
+                if is_vm
+                    && (include.is_empty() || matches(&include, relative))
+                    && !matches(&exclude, relative)
+                {
+                    files.push(entry);
+                }
+            }
         }
+
+        // read_dir order is platform dependent, keep the output stable. Paths
+        // compare by component, so separators don't affect the order either.
+        let entry_class = options
+            .entry
+            .as_deref()
+            .and_then(|entry| entry.split_once('.'))
+            .map(|(class, _)| OsStr::new(class));
+        files.sort_by_cached_key(|file| {
+            let is_entry = entry_class.is_some() && file.file_stem() == entry_class;
+            (!is_entry, file.clone())
+        });
+
+        Ok(files)
+    }
+
+    fn patterns(globs: &[String]) -> Result<Vec<Pattern>> {
+        globs
+            .iter()
+            .map(|glob| Pattern::new(glob).with_context(|| format!("Invalid glob `{}`", glob)))
+            .collect()
     }
 }
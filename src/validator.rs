@@ -0,0 +1,90 @@
+use crate::{
+    diagnostic::{Diagnostic, DiagnosticKind, Diagnostics},
+    op_code::{Instruction, OpCode, Segment},
+    parser::Parser,
+};
+
+const MAX_CONSTANT: u32 = 32767;
+const MAX_POINTER_INDEX: u32 = 1;
+const MAX_TEMP_INDEX: u32 = 7;
+// RAM[16..255] holds every static variable of the program
+const MAX_STATIC_INDEX: u32 = 239;
+
+/// Semantic checks on parsed instructions that the grammar alone can't catch,
+/// run between the `Parser` and the `CodeWriter`.
+pub struct Validator<'a> {
+    filename: &'a str,
+}
+
+impl<'a> Validator<'a> {
+    pub fn new(filename: &'a str) -> Self {
+        Self { filename }
+    }
+
+    pub fn validate(&self, instructions: &[Instruction<'_>]) -> Diagnostics {
+        let mut diagnostics = Diagnostics::new();
+
+        for instruction in instructions {
+            let (op_code, is_pop) = match &instruction.op_code {
+                OpCode::Push(op_code) => (op_code, false),
+                OpCode::Pop(op_code) => (op_code, true),
+                _ => continue,
+            };
+
+            let max = match op_code.segment {
+                Segment::Constant if is_pop => {
+                    diagnostics.push(self.error(instruction, 1, DiagnosticKind::PopToConstant));
+                    continue;
+                }
+                Segment::Constant => {
+                    if op_code.offset > MAX_CONSTANT {
+                        diagnostics.push(self.error(
+                            instruction,
+                            2,
+                            DiagnosticKind::ConstantOutOfRange {
+                                value: op_code.offset,
+                                max: MAX_CONSTANT,
+                            },
+                        ));
+                    }
+                    continue;
+                }
+                Segment::Pointer => MAX_POINTER_INDEX,
+                Segment::Temp => MAX_TEMP_INDEX,
+                Segment::Static => MAX_STATIC_INDEX,
+                Segment::Local | Segment::Argument | Segment::This | Segment::That => continue,
+            };
+
+            if op_code.offset > max {
+                diagnostics.push(self.error(
+                    instruction,
+                    2,
+                    DiagnosticKind::IndexOutOfRange {
+                        segment: op_code.segment,
+                        index: op_code.offset,
+                        max,
+                    },
+                ));
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Reports `kind` at the operand in `position` of the instruction
+    fn error(
+        &self,
+        instruction: &Instruction<'_>,
+        position: usize,
+        kind: DiagnosticKind,
+    ) -> Diagnostic {
+        let tokens = Parser::tokenize(instruction.source);
+        Diagnostic::error(
+            self.filename,
+            instruction.line,
+            instruction.source,
+            tokens[position],
+            kind,
+        )
+    }
+}
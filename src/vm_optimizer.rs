@@ -0,0 +1,152 @@
+use crate::op_code::{OpCode, Segment, SegmentOpCode};
+
+/// Simplifies a file's op codes without changing what they compute:
+///
+/// - arithmetic and logic on constants is folded, with 16-bit wraparound
+/// - `push x; pop x` is dropped
+/// - `not; not` and `neg; neg` are dropped
+/// - `if-goto` on a constant becomes a `goto` or nothing, `neg; if-goto`
+///   loses the `neg` and `eq; not; if-goto` becomes `sub; if-goto`
+///
+/// Rewrites only apply to consecutive op codes, so a `label` in between
+/// always blocks them.
+pub fn optimize<'a>(op_codes: &[OpCode<'a>]) -> Vec<OpCode<'a>> {
+    let mut optimized = Vec::with_capacity(op_codes.len());
+
+    for op_code in op_codes {
+        optimized.push(op_code.clone());
+
+        // every rewrite shortens the program, so this terminates
+        while simplify_tail(&mut optimized) {}
+    }
+
+    optimized
+}
+
+fn push_constant<'a>(value: u32) -> OpCode<'a> {
+    OpCode::Push(SegmentOpCode {
+        segment: Segment::Constant,
+        offset: value,
+    })
+}
+
+/// The shortest op codes pushing `value`, as `push constant` only takes
+/// 0..=32767
+fn constant<'a>(value: i16) -> Vec<OpCode<'a>> {
+    match value {
+        0.. => vec![push_constant(value as u32)],
+        i16::MIN => vec![push_constant(i16::MAX as u32), OpCode::Not],
+        _ => vec![push_constant(-value as u32), OpCode::Neg],
+    }
+}
+
+/// The constant pushed by the op codes at the end of `op_codes`, and how
+/// many op codes push it
+fn constant_at(op_codes: &[OpCode]) -> Option<(i16, usize)> {
+    let value = |op_code: &OpCode| match op_code {
+        OpCode::Push(SegmentOpCode {
+            segment: Segment::Constant,
+            offset,
+        }) => Some(*offset as i16),
+        _ => None,
+    };
+
+    match op_codes {
+        [.., push, OpCode::Neg] if value(push).is_some() => Some((value(push)?.wrapping_neg(), 2)),
+        [.., push, OpCode::Not] if value(push).is_some() => Some((!value(push)?, 2)),
+        [.., push] => Some((value(push)?, 1)),
+        [] => None,
+    }
+}
+
+fn fold_unary(op_code: &OpCode, x: i16) -> Option<i16> {
+    match op_code {
+        OpCode::Neg => Some(x.wrapping_neg()),
+        OpCode::Not => Some(!x),
+        _ => None,
+    }
+}
+
+fn fold_binary(op_code: &OpCode, x: i16, y: i16) -> Option<i16> {
+    let value = match op_code {
+        OpCode::Add => x.wrapping_add(y),
+        OpCode::Sub => x.wrapping_sub(y),
+        OpCode::And => x & y,
+        OpCode::Or => x | y,
+        OpCode::Eq => -((x == y) as i16),
+        OpCode::Gt => -((x > y) as i16),
+        OpCode::Lt => -((x < y) as i16),
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+/// Applies one rewrite to the end of `op_codes`, returning whether it did
+fn simplify_tail(op_codes: &mut Vec<OpCode>) -> bool {
+    let Some((last, rest)) = op_codes.split_last() else {
+        return false;
+    };
+
+    // constant folding, only when it actually shortens the code
+    let folded = if let Some((y, y_len)) = constant_at(rest) {
+        let unary = fold_unary(last, y).map(|value| (value, y_len));
+        let binary = || {
+            let (x, x_len) = constant_at(&rest[..rest.len() - y_len])?;
+            Some((fold_binary(last, x, y)?, x_len + y_len))
+        };
+        unary.or_else(binary)
+    } else {
+        None
+    };
+    if let Some((value, operands_len)) = folded {
+        let replacement = constant(value);
+        if replacement.len() < operands_len + 1 {
+            op_codes.truncate(op_codes.len() - operands_len - 1);
+            op_codes.extend(replacement);
+            return true;
+        }
+    }
+
+    let len = op_codes.len();
+    match &op_codes[..] {
+        [.., OpCode::Not, OpCode::Not] | [.., OpCode::Neg, OpCode::Neg] => {
+            op_codes.truncate(len - 2);
+            true
+        }
+        [.., OpCode::Push(push), OpCode::Pop(pop)]
+            if push == pop && push.segment != Segment::Constant =>
+        {
+            op_codes.truncate(len - 2);
+            true
+        }
+        [.., OpCode::If(target)] => {
+            let target = target.clone();
+            let rest = &op_codes[..len - 1];
+
+            if let Some((condition, condition_len)) = constant_at(rest) {
+                op_codes.truncate(len - 1 - condition_len);
+                if condition != 0 {
+                    op_codes.push(OpCode::Goto(target));
+                }
+                return true;
+            }
+
+            match rest {
+                // a negated value is zero exactly when the value is
+                [.., OpCode::Neg] => {
+                    op_codes.remove(len - 2);
+                    true
+                }
+                // x != y exactly when x - y is non-zero
+                [.., OpCode::Eq, OpCode::Not] => {
+                    op_codes.truncate(len - 3);
+                    op_codes.extend([OpCode::Sub, OpCode::If(target)]);
+                    true
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
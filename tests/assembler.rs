@@ -0,0 +1,62 @@
+use vm_translator::assembler::Program;
+
+#[test]
+fn encodes_a_and_c_instructions() {
+    let program = Program::assemble(
+        "
+        // Computes R0 = 2 + 3
+        @2
+        D=A
+        @3
+        D=D+A
+        @0
+        M=D
+        AMD=!M;JLE
+        (END)
+        @END
+        0;JMP
+        ",
+    )
+    .unwrap();
+
+    assert_eq!(
+        program.to_hack(),
+        "0000000000000010
+1110110000010000
+0000000000000011
+1110000010010000
+0000000000000000
+1110001100001000
+1111110001111110
+0000000000000111
+1110101010000111
+"
+    );
+}
+
+#[test]
+fn resolves_labels_and_variables() {
+    let program = Program::assemble("@counter\n(LOOP)\n@LOOP\n@SCREEN\n@other\n@counter").unwrap();
+
+    assert_eq!(program.encode(), [16, 1, 16384, 17, 16]);
+}
+
+#[test]
+fn rejects_programs_larger_than_rom() {
+    assert!(Program::assemble(&"@0\n".repeat(32768)).is_ok());
+
+    let err = Program::assemble(&"@0\n".repeat(70000)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "program needs 70000 instructions but ROM holds 32768"
+    );
+}
+
+#[test]
+fn rejects_labels_defined_twice() {
+    let err = Program::assemble("(LOOP)\n@LOOP\n(LOOP)\n0;JMP").unwrap_err();
+    assert_eq!(err.to_string(), "line 3: symbol `LOOP` is already defined");
+
+    let err = Program::assemble("@0\n(SP)\n0;JMP").unwrap_err();
+    assert_eq!(err.to_string(), "line 2: symbol `SP` is already defined");
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use vm_translator::{bootstrap::Bootstrap, code_writer::CodeWriter, emulator::Emulator};
+
+fn bootstrap_lines(bootstrap: &Bootstrap) -> Vec<String> {
+    let mut output = Vec::new();
+    let mut code_writer = CodeWriter::new(&mut output, Some(bootstrap));
+    code_writer.flush().unwrap();
+
+    String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .filter(|line| !line.starts_with("//"))
+        .map(|line| line.trim().to_owned())
+        .collect()
+}
+
+#[test]
+fn bootstrap_stores_stack_base_in_sp() {
+    let lines = bootstrap_lines(&Bootstrap::default());
+
+    assert_eq!(lines[..4], ["@256", "D=A", "@SP", "M=D"]);
+}
+
+#[test]
+fn bootstrap_calls_configured_entry() {
+    let lines = bootstrap_lines(&Bootstrap::new(300, "Main.main"));
+
+    assert_eq!(lines[..4], ["@300", "D=A", "@SP", "M=D"]);
+    assert!(lines.iter().any(|line| line == "@Main.main"));
+}
+
+#[test]
+fn bootstrap_initialises_segment_sentinels() {
+    let lines = bootstrap_lines(&Bootstrap::default().with_sentinels());
+
+    assert_eq!(
+        lines[4..20],
+        [
+            "D=-1",
+            "@LCL",
+            "M=D",
+            "@2",
+            "D=-A",
+            "@ARG",
+            "M=D",
+            "@3",
+            "D=-A",
+            "@THIS",
+            "M=D",
+            "@4",
+            "D=-A",
+            "@THAT",
+            "M=D",
+            "@Sys$$ret.0"
+        ]
+    );
+}
+
+#[test]
+fn bootstrap_sets_up_the_stack_before_entering_sys_init() {
+    let sys = "
+        function Sys.init 0
+        push constant 7
+        label HALT
+        goto HALT";
+    let asm = common::translate(
+        &[("Sys", sys)],
+        Some(&Bootstrap::default().with_sentinels()),
+    );
+    let mut emulator = Emulator::from_asm(&asm).unwrap();
+    emulator.run(1000);
+
+    // the return address is followed by the caller's LCL, ARG, THIS and THAT
+    assert_eq!(emulator.ram_slice(257, 4), [-1, -2, -3, -4]);
+    assert_eq!(emulator.ram(1), 261);
+    assert_eq!(emulator.ram(2), 256);
+    assert_eq!(emulator.ram(261), 7);
+    assert_eq!(emulator.ram(0), 262);
+}
@@ -0,0 +1,296 @@
+mod common;
+
+use std::{env, fs, path::PathBuf, process::Command};
+use vm_translator::{
+    bootstrap::Bootstrap,
+    call_graph::{CallGraph, GraphFormat},
+    emulator::{Emulator, StopReason},
+    parser::Parser,
+    translator::{Translation, Translator, TranslatorOptions},
+};
+
+const MAIN: &str = "
+function Main.main 0
+push constant 3
+call Main.double 1
+return
+function Main.double 0
+push argument 0
+push argument 0
+add
+return
+function Main.unused 0
+call Main.double 1
+return
+";
+
+const UTIL: &str = "
+function Util.callback 0
+push constant 0
+return
+function Util.recurse 0
+call Util.recurse 0
+return
+";
+
+const SYS: &str = "
+function Sys.init 0
+call Main.main 0
+pop static 0
+label END
+goto END
+";
+
+fn graph(sources: &[(&str, &str)]) -> CallGraph {
+    let mut graph = CallGraph::new();
+    for (name, source) in sources {
+        let filename = format!("src/{}.vm", name);
+        let parser = Parser::new(&filename, source.as_bytes());
+        graph.add_file(&filename, name, &parser.parse().0);
+    }
+
+    graph
+}
+
+/// Writes the sources to a fresh directory and translates it
+fn translate(name: &str, options: TranslatorOptions) -> (anyhow::Result<Translation>, PathBuf) {
+    let dir = common::write_dir(
+        name,
+        &[("Main.vm", MAIN), ("Util.vm", UTIL), ("Sys.vm", SYS)],
+    );
+
+    let output = dir.join("Out.asm");
+    let translation = Translator::new(
+        dir.to_str().unwrap().to_owned(),
+        output.to_str().unwrap().to_owned(),
+        options,
+    )
+    .translate();
+
+    (translation, output)
+}
+
+#[test]
+fn records_functions_and_their_calls() {
+    let graph = graph(&[("Main", MAIN)]);
+
+    let main = graph.function("Main.main").unwrap();
+    assert_eq!(main.namespace, "Main");
+    assert_eq!(main.line, 2);
+    assert_eq!(main.calls.len(), 1);
+    assert_eq!(main.calls[0].callee, "Main.double");
+    assert_eq!(main.calls[0].num_args, 1);
+    assert_eq!(graph.functions().len(), 3);
+}
+
+#[test]
+fn finds_functions_reachable_from_the_roots() {
+    let graph = graph(&[("Main", MAIN), ("Util", UTIL), ("Sys", SYS)]);
+
+    let mut reachable: Vec<_> = graph.reachable(&["Sys.init"]).into_iter().collect();
+    reachable.sort();
+    assert_eq!(reachable, ["Main.double", "Main.main", "Sys.init"]);
+
+    // recursion and calls to undefined functions don't trip it up
+    let reachable = graph.reachable(&["Util.recurse", "Missing.function"]);
+    assert_eq!(reachable.len(), 1);
+}
+
+#[test]
+fn calls_from_top_level_code_are_roots() {
+    let graph = graph(&[
+        ("Main", MAIN),
+        ("Top", "push constant 1\ncall Main.double 1\n"),
+    ]);
+
+    assert_eq!(graph.top_level_calls().len(), 1);
+    assert!(graph.reachable(&[]).contains("Main.double"));
+}
+
+#[test]
+fn strips_functions_the_entry_cannot_reach() {
+    let options = TranslatorOptions {
+        bootstrap: Some(Bootstrap::default()),
+        strip_unused: true,
+        keep: vec!["Util.callback".to_owned()],
+        ..Default::default()
+    };
+    let (translation, output) = translate("strip", options);
+    let translation = translation.unwrap();
+
+    assert_eq!(
+        translation.removed_functions,
+        ["Main.unused", "Util.recurse"]
+    );
+    assert!(translation.instruction_count < translation.baseline_instruction_count.unwrap());
+
+    let asm = fs::read_to_string(output).unwrap();
+    assert!(!asm.contains("(Main.unused)"));
+    assert!(asm.contains("(Util.callback)"));
+
+    let mut emulator = Emulator::from_asm(&asm).unwrap();
+    assert_eq!(emulator.run(10_000), StopReason::Halted);
+    assert_eq!(emulator.ram(emulator.symbol("Sys.0").unwrap()), 6);
+}
+
+#[test]
+fn refuses_to_keep_undefined_functions() {
+    let options = TranslatorOptions {
+        strip_unused: true,
+        keep: vec!["Util.missing".to_owned()],
+        ..Default::default()
+    };
+    let error = translate("strip-missing", options).0.unwrap_err();
+
+    assert!(error.to_string().contains("`Util.missing` is not defined"));
+}
+
+#[test]
+fn strips_from_the_entry_without_bootstrap_code() {
+    let options = TranslatorOptions {
+        strip_unused: true,
+        entry: Some("Main.main".to_owned()),
+        ..Default::default()
+    };
+    let (translation, _) = translate("strip-entry", options);
+
+    assert_eq!(
+        translation.unwrap().removed_functions,
+        ["Main.unused", "Sys.init", "Util.callback", "Util.recurse"]
+    );
+}
+
+#[test]
+fn strips_libraries_without_sys_init_from_their_kept_functions() {
+    let dir = common::write_dir("strip-library", &[("Main.vm", MAIN), ("Util.vm", UTIL)]);
+    let translate = |keep: &[&str]| {
+        let options = TranslatorOptions {
+            strip_unused: true,
+            keep: keep.iter().map(|name| name.to_string()).collect(),
+            ..Default::default()
+        };
+        Translator::new(
+            dir.to_str().unwrap().to_owned(),
+            dir.join("Out.asm").to_str().unwrap().to_owned(),
+            options,
+        )
+        .translate()
+    };
+
+    assert_eq!(
+        translate(&["Main.main", "Util.callback"])
+            .unwrap()
+            .removed_functions,
+        ["Main.unused", "Util.recurse"]
+    );
+    let error = translate(&[]).unwrap_err();
+    assert!(
+        error.to_string().contains("`Sys.init` is not defined"),
+        "{}",
+        error
+    );
+}
+
+const COUNTED: &str = "function Main.main 1
+push constant 1
+call Main.inc 1
+call Main.inc 1
+push constant 2
+push constant 3
+call Main.add 2
+return
+function Main.inc 0
+return
+function Main.add 0
+return
+";
+
+#[test]
+fn exports_dot_with_call_counts() {
+    let dot = graph(&[("Main", COUNTED)]).export(GraphFormat::Dot);
+
+    assert_eq!(
+        dot,
+        r#"digraph calls {
+  "Main.main" [label="Main.main\nsrc/Main.vm:1, 1 local(s)"];
+  "Main.inc" [label="Main.inc\nsrc/Main.vm:9, 0 local(s)"];
+  "Main.add" [label="Main.add\nsrc/Main.vm:11, 0 local(s)"];
+  "Main.main" -> "Main.inc" [label="1 arg(s) x2"];
+  "Main.main" -> "Main.add" [label="2 arg(s) x1"];
+}
+"#
+    );
+}
+
+#[test]
+fn exports_json_with_call_counts() {
+    let json = graph(&[("Main", COUNTED), ("Top", "call Main.main 0\n")]).export(GraphFormat::Json);
+
+    assert_eq!(
+        json,
+        r#"{
+  "functions": [
+    {"name": "Main.main", "file": "src/Main.vm", "line": 1, "num_locals": 1, "calls": [{"callee": "Main.inc", "num_args": 1, "count": 2}, {"callee": "Main.add", "num_args": 2, "count": 1}]},
+    {"name": "Main.inc", "file": "src/Main.vm", "line": 9, "num_locals": 0, "calls": []},
+    {"name": "Main.add", "file": "src/Main.vm", "line": 11, "num_locals": 0, "calls": []}
+  ],
+  "top_level_calls": [{"callee": "Main.main", "num_args": 0, "count": 1}],
+  "external_functions": []
+}
+"#
+    );
+}
+
+#[test]
+fn shows_undefined_callees_as_external() {
+    let source = "function Main.main 0\ncall Math.multiply 2\ncall Output.printInt 1\n\
+                  call Math.multiply 2\nreturn\n";
+    let graph = graph(&[("Main", source)]);
+
+    assert_eq!(
+        graph.external_functions(),
+        ["Math.multiply", "Output.printInt"]
+    );
+    assert!(graph
+        .export(GraphFormat::Dot)
+        .contains("  \"Math.multiply\" [label=\"Math.multiply\\n(external)\", style=dashed];\n"));
+    assert!(graph
+        .export(GraphFormat::Json)
+        .contains("\"external_functions\": [\"Math.multiply\", \"Output.printInt\"]"));
+}
+
+#[test]
+fn the_command_graphs_programs_without_their_libraries() {
+    let main = "function Main.main 0\ncall Math.multiply 2\ncall Output.printInt 1\nreturn\n";
+    let dir = common::write_dir(
+        "callgraph-cli",
+        &[
+            ("app/Main.vm", main),
+            ("os/Math.vm", "function Math.multiply 0\nreturn\n"),
+        ],
+    );
+
+    let run = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_vm_translator"))
+            .arg("callgraph")
+            .args(args)
+            .args(["--format", "json"])
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let json = run(&["app"]);
+    assert!(json.contains("\"file\": \"app/Main.vm\""), "{}", json);
+    assert!(json.contains("\"external_functions\": [\"Math.multiply\", \"Output.printInt\"]"));
+
+    let json = run(&["app", "-L", "os"]);
+    assert!(
+        json.contains("\"name\": \"Math.multiply\", \"file\": \"os/Math.vm\""),
+        "{}",
+        json
+    );
+    assert!(json.contains("\"external_functions\": [\"Output.printInt\"]"));
+}
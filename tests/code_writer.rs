@@ -0,0 +1,19 @@
+use vm_translator::{
+    code_writer::CodeWriter,
+    op_code::{OpCode, Segment, SegmentOpCode},
+};
+
+#[test]
+fn pop_to_constant_is_an_error() {
+    let mut output = Vec::new();
+    let mut code_writer = CodeWriter::new(&mut output, None);
+    code_writer.set_current_filename("Main");
+
+    let pop = OpCode::Pop(SegmentOpCode {
+        segment: Segment::Constant,
+        offset: 3,
+    });
+    let error = code_writer.write_op_code(&pop).unwrap_err();
+
+    assert_eq!(error.to_string(), "cannot pop to the `constant` segment");
+}
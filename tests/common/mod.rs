@@ -0,0 +1,114 @@
+#![allow(dead_code)]
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use vm_translator::{
+    bootstrap::Bootstrap,
+    code_writer::CodeWriter,
+    emulator::Emulator,
+    parser::Parser,
+    translator::{SourceFile, Translation, Translator, TranslatorOptions},
+};
+
+/// Translates in-memory `(file stem, .vm source)` pairs to Hack assembly
+pub fn translate(sources: &[(&str, &str)], bootstrap: Option<&Bootstrap>) -> String {
+    let options = TranslatorOptions {
+        bootstrap: bootstrap.cloned(),
+        ..TranslatorOptions::default()
+    };
+    translate_with(sources, &options)
+}
+
+/// Like `translate`, with the bootstrap, shared routines and peephole
+/// optimisation level of `options`. VM-level optimisation is left out.
+pub fn translate_with(sources: &[(&str, &str)], options: &TranslatorOptions) -> String {
+    let mut output = Vec::new();
+    let mut code_writer =
+        CodeWriter::new(&mut output, options.bootstrap.as_ref()).with_opt_level(options.opt_level);
+    if options.shared_routines {
+        code_writer = code_writer.with_shared_routines();
+    }
+
+    for (name, source) in sources {
+        let parser = Parser::new(&format!("{}.vm", name), source.as_bytes());
+        let (instructions, diagnostics) = parser.parse();
+        assert!(diagnostics.is_empty(), "{}", diagnostics);
+
+        code_writer.set_current_filename(name);
+        for instruction in instructions {
+            code_writer.write_op_code(&instruction.op_code).unwrap();
+        }
+    }
+
+    code_writer.flush().unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+/// Translates the sources and loads them into an emulator with SP set to 256
+pub fn emulate(sources: &[(&str, &str)], bootstrap: Option<&Bootstrap>) -> Emulator {
+    let mut emulator = Emulator::from_asm(&translate(sources, bootstrap)).unwrap();
+    emulator.set_ram(0, 256);
+    emulator
+}
+
+/// In-memory `(file stem, .vm source)` pairs as translator sources
+pub fn sources(sources: &[(&str, &str)]) -> Vec<SourceFile> {
+    sources
+        .iter()
+        .map(|(name, source)| SourceFile {
+            namespace: name.to_string(),
+            parser: Parser::new(&format!("{}.vm", name), source.as_bytes()),
+        })
+        .collect()
+}
+
+/// A fresh directory under the system temp directory holding the given
+/// `(path, contents)` files, with any subdirectories they need
+pub fn write_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = env::temp_dir().join(format!("vm-translator-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    for (path, contents) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    dir
+}
+
+/// `tests/fixtures/<name>`, the sources of one of the course's programs
+pub fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+/// Translates a fixture with `options` through the file system, like the
+/// command line does: `<name>.vm` when the fixture has one, otherwise its
+/// whole directory
+pub fn translate_fixture(name: &str, options: TranslatorOptions) -> (String, Translation) {
+    // tests in one binary may translate the same fixture at once
+    static OUTPUTS: AtomicUsize = AtomicUsize::new(0);
+    let id = OUTPUTS.fetch_add(1, Ordering::Relaxed);
+
+    let dir = fixture(name);
+    let file = dir.join(format!("{}.vm", name));
+    let input = if file.exists() { file } else { dir };
+    let output = write_dir(&format!("fixture-{}-{}", name, id), &[]).join(format!("{}.asm", name));
+
+    let translation = Translator::new(
+        input.to_str().unwrap().to_owned(),
+        output.to_str().unwrap().to_owned(),
+        options,
+    )
+    .translate()
+    .unwrap();
+
+    let asm = fs::read_to_string(&output).unwrap();
+    fs::remove_dir_all(output.parent().unwrap()).unwrap();
+    (asm, translation)
+}
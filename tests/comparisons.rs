@@ -0,0 +1,72 @@
+mod common;
+
+use vm_translator::emulator::{Emulator, StopReason};
+
+const COMPARISONS: &str = "
+push constant 3
+push constant 3
+eq
+push constant 4
+push constant 3
+gt
+push constant 4
+push constant 3
+lt
+";
+
+fn run(asm: &str) -> Emulator {
+    let mut emulator = Emulator::from_asm(asm).unwrap();
+    emulator.set_ram(0, 256);
+    assert_eq!(emulator.run(1_000), StopReason::Halted);
+    emulator
+}
+
+#[test]
+fn comparisons_survive_code_placed_before_them() {
+    let asm = common::translate(&[("Main", COMPARISONS)], None);
+    let shifted = format!("@42\nD=A\n@R13\nM=D\n{}", asm);
+
+    assert_eq!(run(&asm).ram_slice(256, 3), [-1, -1, 0]);
+    assert_eq!(run(&shifted).ram_slice(256, 3), [-1, -1, 0]);
+}
+
+#[test]
+fn separately_translated_files_can_be_concatenated() {
+    let first = common::translate(&[("First", COMPARISONS)], None);
+    let second = common::translate(&[("Second", COMPARISONS)], None);
+
+    let emulator = run(&format!("{}{}", first, second));
+    assert_eq!(emulator.ram(0), 262);
+    assert_eq!(emulator.ram_slice(256, 6), [-1, -1, 0, -1, -1, 0]);
+}
+
+#[test]
+fn compares_operands_whose_difference_overflows() {
+    // 32767 > -2 and -32768 < 1, although both differences overflow
+    let source = "
+push constant 32767
+push constant 2
+neg
+gt
+push constant 32767
+not
+push constant 1
+lt
+push constant 32767
+push constant 2
+neg
+lt
+push constant 2
+neg
+push constant 32767
+gt
+push constant 5
+neg
+push constant 3
+neg
+lt
+";
+    let asm = common::translate(&[("Main", source)], None);
+
+    assert_eq!(run(&asm).ram_slice(256, 5), [-1, -1, 0, 0, -1]);
+}
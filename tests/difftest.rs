@@ -0,0 +1,229 @@
+mod common;
+
+use std::fs;
+use vm_translator::{
+    bootstrap::Bootstrap,
+    difftest::{parse_ram_setting, DiffTest, DivergenceKind, Outcome},
+    translator::{SourceOptions, Translator},
+};
+
+#[test]
+fn agrees_on_stack_arithmetic_and_segments() {
+    let sources = common::sources(&[(
+        "Main",
+        "push constant 3030
+        pop pointer 0
+        push constant 12
+        push constant 30
+        lt
+        pop this 2
+        push constant 7
+        pop static 4
+        push static 4
+        push this 2
+        push constant 5
+        neg
+        or
+        push constant 9
+        push constant 9
+        eq
+        not",
+    )]);
+    let (files, diagnostics) = Translator::parse_sources(&sources);
+    assert!(diagnostics.is_empty());
+
+    let outcome = DiffTest::new(&sources, files, None).run(1000).unwrap();
+
+    assert_eq!(outcome, Outcome::Agreed { steps: 17 });
+}
+
+#[test]
+fn agrees_on_loops_until_halted() {
+    let sources = common::sources(&[(
+        "Sys",
+        "function Sys.init 0
+        push constant 10
+        pop temp 0
+        label LOOP
+        push temp 0
+        push constant 1
+        sub
+        pop temp 0
+        push temp 0
+        if-goto LOOP
+        label HALT
+        goto HALT",
+    )]);
+    let (files, _) = Translator::parse_sources(&sources);
+
+    let outcome = DiffTest::new(&sources, files, Some(Bootstrap::default()))
+        .run(1000)
+        .unwrap();
+
+    assert_eq!(outcome, Outcome::Agreed { steps: 74 });
+}
+
+#[test]
+fn agrees_that_temp_survives_calls() {
+    let sources = common::sources(&[(
+        "Sys",
+        "function Sys.init 0
+        push constant 4
+        pop temp 1
+        call Sys.answer 0
+        label HALT
+        goto HALT
+        function Sys.answer 0
+        push constant 42
+        return",
+    )]);
+    let (files, _) = Translator::parse_sources(&sources);
+
+    let outcome = DiffTest::new(&sources, files, Some(Bootstrap::default()))
+        .run(1000)
+        .unwrap();
+
+    assert!(matches!(outcome, Outcome::Agreed { .. }), "{:?}", outcome);
+}
+
+#[test]
+fn agrees_on_comparisons_whose_difference_overflows() {
+    let sources = common::sources(&[(
+        "Main",
+        "push constant 32767
+        push constant 2
+        neg
+        gt
+        push constant 2
+        neg
+        push constant 32767
+        lt
+        push constant 32767
+        push constant 2
+        neg
+        lt",
+    )]);
+    let (files, _) = Translator::parse_sources(&sources);
+
+    let outcome = DiffTest::new(&sources, files, None).run(1000).unwrap();
+
+    assert!(matches!(outcome, Outcome::Agreed { .. }), "{:?}", outcome);
+}
+
+const PEEK: &str = "function Sys.init 0
+call Sys.peek 0
+label HALT
+goto HALT
+
+function Sys.peek 0
+push constant 1
+pop temp 0
+push argument 0
+return
+";
+
+#[test]
+fn reports_the_first_diverging_op_code_at_its_source_path() {
+    // `argument 0` of a function taking no arguments is its return address,
+    // a program index to the interpreter and a ROM address to the assembly
+    let dir = common::write_dir("difftest-divergence", &[("src/Sys.vm", PEEK)]);
+    let input = dir.join("src");
+    let sources =
+        Translator::load_sources(&[input.to_str().unwrap()], &SourceOptions::default()).unwrap();
+    let (files, _) = Translator::parse_sources(&sources);
+
+    let outcome = DiffTest::new(&sources, files, Some(Bootstrap::default()))
+        .run(1000)
+        .unwrap();
+
+    let Outcome::Diverged(divergence) = outcome else {
+        panic!("expected a divergence, got {:?}", outcome);
+    };
+    let path = input.join("Sys.vm");
+    assert!(divergence
+        .to_string()
+        .contains(&format!("after `push argument 0` ({}:9)", path.display())));
+    assert_eq!(divergence.file, path.to_str().unwrap());
+    let DivergenceKind::State(mismatches) = divergence.kind else {
+        panic!("expected a state mismatch");
+    };
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!(mismatches[0].location, "RAM[266]");
+    assert_eq!(mismatches[0].expected, 2);
+}
+
+/// A fixture directory, whether it is bootstrapped, and the RAM its course
+/// test script sets before running it
+type Fixture = (&'static str, bool, &'static [(u16, i16)]);
+
+const FIXTURES: &[Fixture] = &[
+    ("BasicLoop", false, &[(400, 3)]),
+    ("BasicTest", false, &[]),
+    ("FibonacciElement", true, &[]),
+    ("FibonacciSeries", false, &[(400, 6), (401, 3000)]),
+    ("NestedCall", true, &[]),
+    ("PointerTest", false, &[]),
+    ("SimpleAdd", false, &[]),
+    (
+        "SimpleFunction",
+        false,
+        &[
+            (0, 317),
+            (1, 317),
+            (2, 310),
+            (3, 3000),
+            (4, 4000),
+            (310, 1234),
+            (311, 37),
+            (312, 9),
+            (313, 305),
+            (314, 300),
+            (315, 3010),
+            (316, 4010),
+        ],
+    ),
+    ("StackTest", false, &[]),
+    ("StaticTest", false, &[]),
+    ("StaticsTest", true, &[]),
+];
+
+#[test]
+fn agrees_on_every_fixture() {
+    let mut names: Vec<_> = fs::read_dir(common::fixture(""))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    let listed: Vec<_> = FIXTURES.iter().map(|(name, ..)| name.to_string()).collect();
+    assert_eq!(names, listed, "every fixture needs its initial RAM listed");
+
+    for (name, bootstrap, ram) in FIXTURES {
+        let dir = common::fixture(name);
+        let sources =
+            Translator::load_sources(&[dir.to_str().unwrap()], &SourceOptions::default()).unwrap();
+        let (files, diagnostics) = Translator::parse_sources(&sources);
+        assert!(diagnostics.is_empty(), "{}", diagnostics);
+
+        let outcome = DiffTest::new(&sources, files, bootstrap.then(Bootstrap::default))
+            .with_ram(ram.iter().copied())
+            .run(100_000)
+            .unwrap();
+
+        assert!(
+            matches!(outcome, Outcome::Agreed { .. }),
+            "{}: {:?}",
+            name,
+            outcome
+        );
+    }
+}
+
+#[test]
+fn parses_ram_settings() {
+    assert_eq!(parse_ram_setting("SP=317").unwrap(), (0, 317));
+    assert_eq!(parse_ram_setting("THAT=4000").unwrap(), (4, 4000));
+    assert_eq!(parse_ram_setting("RAM[400]=-6").unwrap(), (400, -6));
+    assert!(parse_ram_setting("SP").is_err());
+    assert!(parse_ram_setting("local=300").is_err());
+    assert!(parse_ram_setting("RAM[400]=40000").is_err());
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use std::path::{Path, PathBuf};
+use vm_translator::translator::{SourceOptions, Translator};
+
+const EMPTY: &str = "// nothing to see";
+
+/// A fresh directory holding each path as an empty .vm source
+fn write_dir(name: &str, paths: &[&str]) -> PathBuf {
+    let files: Vec<_> = paths.iter().map(|path| (*path, EMPTY)).collect();
+    common::write_dir(name, &files)
+}
+
+/// The files loaded from `dir`, relative to it and in load order
+fn discover(dir: &Path, options: &SourceOptions) -> anyhow::Result<Vec<String>> {
+    let sources = Translator::load_sources(&[dir.to_str().unwrap()], options)?;
+    Ok(sources
+        .iter()
+        .map(|source| {
+            let path = Path::new(source.parser.filename())
+                .strip_prefix(dir)
+                .unwrap();
+            path.components()
+                .map(|component| component.as_os_str().to_str().unwrap())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .collect())
+}
+
+#[test]
+fn only_picks_up_vm_files_in_sorted_order() {
+    let dir = write_dir(
+        "discovery-extension",
+        &[
+            "Sys.vm",
+            "Main.vm",
+            "foo.xvm",
+            "Bar.vmx",
+            "svm/Nested.vm",
+            "Array.vm",
+        ],
+    );
+
+    assert_eq!(
+        discover(&dir, &SourceOptions::default()).unwrap(),
+        ["Array.vm", "Main.vm", "Sys.vm"]
+    );
+}
+
+#[test]
+fn recursive_discovery_walks_subdirectories() {
+    let dir = write_dir(
+        "discovery-recursive",
+        &["Main.vm", "os/Math.vm", "os/Array.vm", "lib/deep/Util.vm"],
+    );
+    let options = SourceOptions {
+        recursive: true,
+        ..SourceOptions::default()
+    };
+
+    assert_eq!(
+        discover(&dir, &options).unwrap(),
+        ["Main.vm", "lib/deep/Util.vm", "os/Array.vm", "os/Math.vm"]
+    );
+}
+
+#[test]
+fn recursive_discovery_reports_clashing_namespaces() {
+    let dir = write_dir("discovery-clash", &["app/Main.vm", "test/Main.vm"]);
+    let options = SourceOptions {
+        recursive: true,
+        ..SourceOptions::default()
+    };
+
+    let err = discover(&dir, &options).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("share the static namespace `Main`"),
+        "{}",
+        err
+    );
+
+    let options = SourceOptions {
+        prefix_namespaces: true,
+        ..options
+    };
+    assert_eq!(
+        discover(&dir, &options).unwrap(),
+        ["app/Main.vm", "test/Main.vm"]
+    );
+}
+
+#[test]
+fn include_and_exclude_globs_filter_files() {
+    let dir = write_dir(
+        "discovery-globs",
+        &["Main.vm", "MainTest.vm", "os/Math.vm", "os/MathTest.vm"],
+    );
+
+    let options = SourceOptions {
+        recursive: true,
+        exclude: vec!["*Test.vm".to_owned()],
+        ..SourceOptions::default()
+    };
+    assert_eq!(discover(&dir, &options).unwrap(), ["Main.vm", "os/Math.vm"]);
+
+    let options = SourceOptions {
+        recursive: true,
+        include: vec!["os/*".to_owned()],
+        exclude: vec!["*Test.vm".to_owned()],
+        ..SourceOptions::default()
+    };
+    assert_eq!(discover(&dir, &options).unwrap(), ["os/Math.vm"]);
+
+    let options = SourceOptions {
+        include: vec!["[".to_owned()],
+        ..SourceOptions::default()
+    };
+    assert!(discover(&dir, &options).is_err());
+}
+
+#[test]
+fn the_entry_file_comes_first() {
+    let dir = write_dir("discovery-entry", &["Main.vm", "Sys.vm", "Array.vm"]);
+    let options = SourceOptions {
+        entry: Some("Sys.init".to_owned()),
+        ..SourceOptions::default()
+    };
+
+    assert_eq!(
+        discover(&dir, &options).unwrap(),
+        ["Sys.vm", "Array.vm", "Main.vm"]
+    );
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use vm_translator::emulator::{Emulator, StopReason};
+
+#[test]
+fn runs_until_end_loop() {
+    let mut emulator = Emulator::from_asm(
+        "
+        @2
+        D=A
+        @3
+        D=D+A // 5
+        @0
+        M=D
+        (END)
+        @END
+        0;JMP
+        ",
+    )
+    .unwrap();
+
+    assert_eq!(emulator.run(100), StopReason::Halted);
+    assert_eq!(emulator.ram(0), 5);
+    assert_eq!(emulator.d(), 5);
+}
+
+#[test]
+fn stops_at_breakpoints_and_resumes() {
+    let mut emulator = Emulator::from_asm(
+        "
+        @i
+        M=1
+        (LOOP)
+        @i
+        MD=M+1
+        @LOOP
+        D;JGT
+        ",
+    )
+    .unwrap();
+    let loop_address = emulator.symbol("LOOP").unwrap();
+    emulator.add_breakpoint(loop_address);
+
+    assert_eq!(emulator.run(100), StopReason::Breakpoint(loop_address));
+    assert_eq!(emulator.run(100), StopReason::Breakpoint(loop_address));
+    assert_eq!(emulator.ram(16), 2);
+
+    emulator.remove_breakpoint(loop_address);
+    assert_eq!(emulator.run(10), StopReason::CycleLimit);
+    assert_eq!(emulator.cycles(), 16);
+}
+
+#[test]
+fn allocates_variables_from_ram_16() {
+    let emulator = Emulator::from_asm("@first\n@second\n@first\n@R15").unwrap();
+
+    assert_eq!(emulator.symbol("first"), Some(16));
+    assert_eq!(emulator.symbol("second"), Some(17));
+}
+
+#[test]
+fn rejects_invalid_instructions() {
+    assert!(Emulator::from_asm("D=D+X").is_err());
+    assert!(Emulator::from_asm("@40000").is_err());
+    assert!(Emulator::from_asm("0;JUMP").is_err());
+}
+
+#[test]
+fn executes_arithmetic_written_by_code_writer() {
+    let mut emulator = common::emulate(
+        &[(
+            "Main",
+            "push constant 7
+            push constant 8
+            add
+            push constant 3
+            sub
+            push constant 12
+            eq
+            push constant 5
+            push constant 9
+            lt
+            push constant 5
+            neg
+            not",
+        )],
+        None,
+    );
+    emulator.run(1000);
+
+    assert_eq!(emulator.ram(0), 259);
+    assert_eq!(emulator.ram_slice(256, 3), [-1, -1, 4]);
+}
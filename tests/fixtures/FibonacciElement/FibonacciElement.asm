@@ -0,0 +1,513 @@
+// bootstrap
+	@256
+	D=A
+	@SP
+	M=D
+	@Sys$$ret.0
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	@LCL
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	@ARG
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	@THIS
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	@THAT
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	@SP
+	D=M
+	@0
+	D=D-A
+	@5
+	D=D-A
+	@ARG
+	M=D
+	@SP
+	D=M
+	@LCL
+	M=D
+	@Sys.init
+	0;JMP
+(Sys$$ret.0)
+// function Main.fibonacci 0
+(Main.fibonacci)
+// push argument 0
+	@ARG
+	D=M
+	@0
+	A=D+A
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 2
+	@2
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// lt
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	@R13
+	M=D
+	@SP
+	A=M
+	D=M
+	@Main.fibonacci$$cmp.0.neg
+	D;JLT
+	@R13
+	D=M
+	@Main.fibonacci$$cmp.0.same
+	D;JGE
+	D=1
+	@Main.fibonacci$$cmp.0.ordered
+	0;JMP
+(Main.fibonacci$$cmp.0.neg)
+	@R13
+	D=M
+	@Main.fibonacci$$cmp.0.same
+	D;JLT
+	D=-1
+	@Main.fibonacci$$cmp.0.ordered
+	0;JMP
+(Main.fibonacci$$cmp.0.same)
+	@R13
+	D=M
+	@SP
+	A=M
+	D=M-D
+(Main.fibonacci$$cmp.0.ordered)
+	@Main.fibonacci$$cmp.0
+	D;JLT
+	D=0
+	@Main.fibonacci$$cmp.0.end
+	0;JMP
+(Main.fibonacci$$cmp.0)
+	D=-1
+(Main.fibonacci$$cmp.0.end)
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// if-goto IF_TRUE
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@Main.fibonacci$IF_TRUE
+	D;JNE
+// goto IF_FALSE
+	@Main.fibonacci$IF_FALSE
+	0;JMP
+// label IF_TRUE
+(Main.fibonacci$IF_TRUE)
+// push argument 0
+	@ARG
+	D=M
+	@0
+	A=D+A
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// return
+	@LCL
+	D=M
+	@R14
+	M=D
+	@R14
+	D=M
+	@5
+	D=D-A
+	A=D
+	D=M
+	@R15
+	M=D
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@ARG
+	A=M
+	M=D
+	@ARG
+	D=M+1
+	@SP
+	M=D
+	@R14
+	D=M
+	@1
+	D=D-A
+	A=D
+	D=M
+	@THAT
+	M=D
+	@R14
+	D=M
+	@2
+	D=D-A
+	A=D
+	D=M
+	@THIS
+	M=D
+	@R14
+	D=M
+	@3
+	D=D-A
+	A=D
+	D=M
+	@ARG
+	M=D
+	@R14
+	D=M
+	@4
+	D=D-A
+	A=D
+	D=M
+	@LCL
+	M=D
+	@R15
+	A=M
+	0;JMP
+// label IF_FALSE
+(Main.fibonacci$IF_FALSE)
+// push argument 0
+	@ARG
+	D=M
+	@0
+	A=D+A
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 2
+	@2
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// sub
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	D=M-D
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// call Main.fibonacci 1
+	@Main.fibonacci$$ret.0
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	@LCL
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	@ARG
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	@THIS
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	@THAT
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	@SP
+	D=M
+	@1
+	D=D-A
+	@5
+	D=D-A
+	@ARG
+	M=D
+	@SP
+	D=M
+	@LCL
+	M=D
+	@Main.fibonacci
+	0;JMP
+(Main.fibonacci$$ret.0)
+// push argument 0
+	@ARG
+	D=M
+	@0
+	A=D+A
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 1
+	@1
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// sub
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	D=M-D
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// call Main.fibonacci 1
+	@Main.fibonacci$$ret.1
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	@LCL
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	@ARG
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	@THIS
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	@THAT
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	@SP
+	D=M
+	@1
+	D=D-A
+	@5
+	D=D-A
+	@ARG
+	M=D
+	@SP
+	D=M
+	@LCL
+	M=D
+	@Main.fibonacci
+	0;JMP
+(Main.fibonacci$$ret.1)
+// add
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	D=D+M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// return
+	@LCL
+	D=M
+	@R14
+	M=D
+	@R14
+	D=M
+	@5
+	D=D-A
+	A=D
+	D=M
+	@R15
+	M=D
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@ARG
+	A=M
+	M=D
+	@ARG
+	D=M+1
+	@SP
+	M=D
+	@R14
+	D=M
+	@1
+	D=D-A
+	A=D
+	D=M
+	@THAT
+	M=D
+	@R14
+	D=M
+	@2
+	D=D-A
+	A=D
+	D=M
+	@THIS
+	M=D
+	@R14
+	D=M
+	@3
+	D=D-A
+	A=D
+	D=M
+	@ARG
+	M=D
+	@R14
+	D=M
+	@4
+	D=D-A
+	A=D
+	D=M
+	@LCL
+	M=D
+	@R15
+	A=M
+	0;JMP
+// function Sys.init 0
+(Sys.init)
+// push constant 4
+	@4
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// call Main.fibonacci 1
+	@Sys.init$$ret.0
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	@LCL
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	@ARG
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	@THIS
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	@THAT
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	@SP
+	D=M
+	@1
+	D=D-A
+	@5
+	D=D-A
+	@ARG
+	M=D
+	@SP
+	D=M
+	@LCL
+	M=D
+	@Main.fibonacci
+	0;JMP
+(Sys.init$$ret.0)
+// label WHILE
+(Sys.init$WHILE)
+// goto WHILE
+	@Sys.init$WHILE
+	0;JMP
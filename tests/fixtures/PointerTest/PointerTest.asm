@@ -0,0 +1,161 @@
+// push constant 3030
+	@3030
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// pop pointer 0
+	@3
+	D=A
+	@R13
+	M=D
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@R13
+	A=M
+	M=D
+// push constant 3040
+	@3040
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// pop pointer 1
+	@4
+	D=A
+	@R13
+	M=D
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@R13
+	A=M
+	M=D
+// push constant 32
+	@32
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// pop this 2
+	@THIS
+	D=M
+	@2
+	D=D+A
+	@R13
+	M=D
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@R13
+	A=M
+	M=D
+// push constant 46
+	@46
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// pop that 6
+	@THAT
+	D=M
+	@6
+	D=D+A
+	@R13
+	M=D
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@R13
+	A=M
+	M=D
+// push pointer 0
+	@3
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push pointer 1
+	@4
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// add
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	D=D+M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push this 2
+	@THIS
+	D=M
+	@2
+	A=D+A
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// sub
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	D=M-D
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push that 6
+	@THAT
+	D=M
+	@6
+	A=D+A
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// add
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	D=D+M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
@@ -0,0 +1,167 @@
+// function SimpleFunction.test 2
+(SimpleFunction.test)
+	D=0
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+	D=0
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push local 0
+	@LCL
+	D=M
+	@0
+	A=D+A
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push local 1
+	@LCL
+	D=M
+	@1
+	A=D+A
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// add
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	D=D+M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// not
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	D=!D
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push argument 0
+	@ARG
+	D=M
+	@0
+	A=D+A
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// add
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	D=D+M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push argument 1
+	@ARG
+	D=M
+	@1
+	A=D+A
+	D=M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// sub
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	D=M-D
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// return
+	@LCL
+	D=M
+	@R14
+	M=D
+	@R14
+	D=M
+	@5
+	D=D-A
+	A=D
+	D=M
+	@R15
+	M=D
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@ARG
+	A=M
+	M=D
+	@ARG
+	D=M+1
+	@SP
+	M=D
+	@R14
+	D=M
+	@1
+	D=D-A
+	A=D
+	D=M
+	@THAT
+	M=D
+	@R14
+	D=M
+	@2
+	D=D-A
+	A=D
+	D=M
+	@THIS
+	M=D
+	@R14
+	D=M
+	@3
+	D=D-A
+	A=D
+	D=M
+	@ARG
+	M=D
+	@R14
+	D=M
+	@4
+	D=D-A
+	A=D
+	D=M
+	@LCL
+	M=D
+	@R15
+	A=M
+	0;JMP
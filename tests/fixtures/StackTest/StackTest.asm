@@ -0,0 +1,615 @@
+// push constant 17
+	@17
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 17
+	@17
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// eq
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	D=M-D
+	@StackTest$$cmp.0
+	D;JEQ
+	D=0
+	@StackTest$$cmp.0.end
+	0;JMP
+(StackTest$$cmp.0)
+	D=-1
+(StackTest$$cmp.0.end)
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 17
+	@17
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 16
+	@16
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// eq
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	D=M-D
+	@StackTest$$cmp.1
+	D;JEQ
+	D=0
+	@StackTest$$cmp.1.end
+	0;JMP
+(StackTest$$cmp.1)
+	D=-1
+(StackTest$$cmp.1.end)
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 16
+	@16
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 17
+	@17
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// eq
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	D=M-D
+	@StackTest$$cmp.2
+	D;JEQ
+	D=0
+	@StackTest$$cmp.2.end
+	0;JMP
+(StackTest$$cmp.2)
+	D=-1
+(StackTest$$cmp.2.end)
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 892
+	@892
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 891
+	@891
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// lt
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	@R13
+	M=D
+	@SP
+	A=M
+	D=M
+	@StackTest$$cmp.3.neg
+	D;JLT
+	@R13
+	D=M
+	@StackTest$$cmp.3.same
+	D;JGE
+	D=1
+	@StackTest$$cmp.3.ordered
+	0;JMP
+(StackTest$$cmp.3.neg)
+	@R13
+	D=M
+	@StackTest$$cmp.3.same
+	D;JLT
+	D=-1
+	@StackTest$$cmp.3.ordered
+	0;JMP
+(StackTest$$cmp.3.same)
+	@R13
+	D=M
+	@SP
+	A=M
+	D=M-D
+(StackTest$$cmp.3.ordered)
+	@StackTest$$cmp.3
+	D;JLT
+	D=0
+	@StackTest$$cmp.3.end
+	0;JMP
+(StackTest$$cmp.3)
+	D=-1
+(StackTest$$cmp.3.end)
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 891
+	@891
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 892
+	@892
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// lt
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	@R13
+	M=D
+	@SP
+	A=M
+	D=M
+	@StackTest$$cmp.4.neg
+	D;JLT
+	@R13
+	D=M
+	@StackTest$$cmp.4.same
+	D;JGE
+	D=1
+	@StackTest$$cmp.4.ordered
+	0;JMP
+(StackTest$$cmp.4.neg)
+	@R13
+	D=M
+	@StackTest$$cmp.4.same
+	D;JLT
+	D=-1
+	@StackTest$$cmp.4.ordered
+	0;JMP
+(StackTest$$cmp.4.same)
+	@R13
+	D=M
+	@SP
+	A=M
+	D=M-D
+(StackTest$$cmp.4.ordered)
+	@StackTest$$cmp.4
+	D;JLT
+	D=0
+	@StackTest$$cmp.4.end
+	0;JMP
+(StackTest$$cmp.4)
+	D=-1
+(StackTest$$cmp.4.end)
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 891
+	@891
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 891
+	@891
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// lt
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	@R13
+	M=D
+	@SP
+	A=M
+	D=M
+	@StackTest$$cmp.5.neg
+	D;JLT
+	@R13
+	D=M
+	@StackTest$$cmp.5.same
+	D;JGE
+	D=1
+	@StackTest$$cmp.5.ordered
+	0;JMP
+(StackTest$$cmp.5.neg)
+	@R13
+	D=M
+	@StackTest$$cmp.5.same
+	D;JLT
+	D=-1
+	@StackTest$$cmp.5.ordered
+	0;JMP
+(StackTest$$cmp.5.same)
+	@R13
+	D=M
+	@SP
+	A=M
+	D=M-D
+(StackTest$$cmp.5.ordered)
+	@StackTest$$cmp.5
+	D;JLT
+	D=0
+	@StackTest$$cmp.5.end
+	0;JMP
+(StackTest$$cmp.5)
+	D=-1
+(StackTest$$cmp.5.end)
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 32767
+	@32767
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 32766
+	@32766
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// gt
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	@R13
+	M=D
+	@SP
+	A=M
+	D=M
+	@StackTest$$cmp.6.neg
+	D;JLT
+	@R13
+	D=M
+	@StackTest$$cmp.6.same
+	D;JGE
+	D=1
+	@StackTest$$cmp.6.ordered
+	0;JMP
+(StackTest$$cmp.6.neg)
+	@R13
+	D=M
+	@StackTest$$cmp.6.same
+	D;JLT
+	D=-1
+	@StackTest$$cmp.6.ordered
+	0;JMP
+(StackTest$$cmp.6.same)
+	@R13
+	D=M
+	@SP
+	A=M
+	D=M-D
+(StackTest$$cmp.6.ordered)
+	@StackTest$$cmp.6
+	D;JGT
+	D=0
+	@StackTest$$cmp.6.end
+	0;JMP
+(StackTest$$cmp.6)
+	D=-1
+(StackTest$$cmp.6.end)
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 32766
+	@32766
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 32767
+	@32767
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// gt
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	@R13
+	M=D
+	@SP
+	A=M
+	D=M
+	@StackTest$$cmp.7.neg
+	D;JLT
+	@R13
+	D=M
+	@StackTest$$cmp.7.same
+	D;JGE
+	D=1
+	@StackTest$$cmp.7.ordered
+	0;JMP
+(StackTest$$cmp.7.neg)
+	@R13
+	D=M
+	@StackTest$$cmp.7.same
+	D;JLT
+	D=-1
+	@StackTest$$cmp.7.ordered
+	0;JMP
+(StackTest$$cmp.7.same)
+	@R13
+	D=M
+	@SP
+	A=M
+	D=M-D
+(StackTest$$cmp.7.ordered)
+	@StackTest$$cmp.7
+	D;JGT
+	D=0
+	@StackTest$$cmp.7.end
+	0;JMP
+(StackTest$$cmp.7)
+	D=-1
+(StackTest$$cmp.7.end)
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 32766
+	@32766
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 32766
+	@32766
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// gt
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	@R13
+	M=D
+	@SP
+	A=M
+	D=M
+	@StackTest$$cmp.8.neg
+	D;JLT
+	@R13
+	D=M
+	@StackTest$$cmp.8.same
+	D;JGE
+	D=1
+	@StackTest$$cmp.8.ordered
+	0;JMP
+(StackTest$$cmp.8.neg)
+	@R13
+	D=M
+	@StackTest$$cmp.8.same
+	D;JLT
+	D=-1
+	@StackTest$$cmp.8.ordered
+	0;JMP
+(StackTest$$cmp.8.same)
+	@R13
+	D=M
+	@SP
+	A=M
+	D=M-D
+(StackTest$$cmp.8.ordered)
+	@StackTest$$cmp.8
+	D;JGT
+	D=0
+	@StackTest$$cmp.8.end
+	0;JMP
+(StackTest$$cmp.8)
+	D=-1
+(StackTest$$cmp.8.end)
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 57
+	@57
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 31
+	@31
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 53
+	@53
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// add
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	D=D+M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 112
+	@112
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// sub
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	D=M-D
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// neg
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	D=-D
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// and
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	D=D&M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// push constant 82
+	@82
+	D=A
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// or
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	@SP
+	AM=M-1
+	D=D|M
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
+// not
+	@SP
+	AM=M-1
+	D=M
+	M=0
+	D=!D
+	@SP
+	A=M
+	M=D
+	@SP
+	M=M+1
@@ -0,0 +1,191 @@
+mod common;
+
+use std::{env, fs};
+use vm_translator::{
+    bootstrap::Bootstrap,
+    emulator::{Emulator, StopReason},
+    translator::TranslatorOptions,
+};
+
+/// Translates `tests/fixtures/<name>`, checking the output against the
+/// `<name>.asm` golden file next to the sources. Run with `UPDATE_GOLDEN=1`
+/// to rewrite the golden files after an intended change to the output.
+fn translate(name: &str, bootstrap: bool) -> String {
+    let options = TranslatorOptions {
+        bootstrap: bootstrap.then(Bootstrap::default),
+        ..Default::default()
+    };
+    let (asm, _) = common::translate_fixture(name, options);
+
+    let golden = common::fixture(name).join(format!("{}.asm", name));
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&golden, &asm).unwrap();
+    } else {
+        let expected = fs::read_to_string(&golden).unwrap();
+        assert!(
+            asm == expected,
+            "{} differs from {}, rerun with UPDATE_GOLDEN=1 if the change is intended",
+            name,
+            golden.display()
+        );
+    }
+
+    asm
+}
+
+/// Translates a fixture and runs it on the emulator from the given RAM
+/// state until it halts
+fn run(name: &str, bootstrap: bool, ram: &[(u16, i16)]) -> Emulator {
+    let mut emulator = Emulator::from_asm(&translate(name, bootstrap)).unwrap();
+    for &(address, value) in ram {
+        emulator.set_ram(address, value);
+    }
+
+    assert_eq!(emulator.run(1_000_000), StopReason::Halted);
+    emulator
+}
+
+fn assert_ram(emulator: &Emulator, expected: &[(u16, i16)]) {
+    for &(address, value) in expected {
+        assert_eq!(emulator.ram(address), value, "RAM[{}]", address);
+    }
+}
+
+// SP, LCL, ARG, THIS and THAT as set by the course's test scripts
+const POINTERS: [(u16, i16); 5] = [(0, 256), (1, 300), (2, 400), (3, 3000), (4, 3010)];
+
+#[test]
+fn simple_add() {
+    let emulator = run("SimpleAdd", false, &[(0, 256)]);
+    assert_ram(&emulator, &[(0, 257), (256, 15)]);
+}
+
+#[test]
+fn stack_test() {
+    let emulator = run("StackTest", false, &[(0, 256)]);
+
+    assert_eq!(emulator.ram(0), 266);
+    assert_eq!(
+        emulator.ram_slice(256, 10),
+        [-1, 0, 0, 0, -1, 0, -1, 0, 0, -91]
+    );
+}
+
+#[test]
+fn basic_test() {
+    let emulator = run("BasicTest", false, &POINTERS);
+    assert_ram(
+        &emulator,
+        &[
+            (256, 472),
+            (300, 10),
+            (401, 21),
+            (402, 22),
+            (3006, 36),
+            (3012, 42),
+            (3015, 45),
+            (11, 510),
+        ],
+    );
+}
+
+#[test]
+fn pointer_test() {
+    let emulator = run("PointerTest", false, &[(0, 256)]);
+    assert_ram(
+        &emulator,
+        &[(256, 6084), (3, 3030), (4, 3040), (3032, 32), (3046, 46)],
+    );
+}
+
+#[test]
+fn static_test() {
+    let emulator = run("StaticTest", false, &[(0, 256)]);
+    assert_ram(&emulator, &[(256, 1110)]);
+}
+
+#[test]
+fn basic_loop() {
+    let emulator = run(
+        "BasicLoop",
+        false,
+        &[(0, 256), (1, 300), (2, 400), (400, 3)],
+    );
+    assert_ram(&emulator, &[(0, 257), (256, 6)]);
+}
+
+#[test]
+fn fibonacci_series() {
+    let emulator = run(
+        "FibonacciSeries",
+        false,
+        &[(0, 256), (1, 300), (2, 400), (400, 6), (401, 3000)],
+    );
+    assert_eq!(emulator.ram_slice(3000, 6), [0, 1, 1, 2, 3, 5]);
+}
+
+#[test]
+fn simple_function() {
+    // the course's script runs the function body with a frame already in place
+    let emulator = run(
+        "SimpleFunction",
+        false,
+        &[
+            (0, 317),
+            (1, 317),
+            (2, 310),
+            (3, 3000),
+            (4, 4000),
+            (310, 1234),
+            (311, 37),
+            (312, 1000),
+            (313, 305),
+            (314, 300),
+            (315, 3010),
+            (316, 4010),
+        ],
+    );
+    assert_ram(
+        &emulator,
+        &[
+            (0, 311),
+            (1, 305),
+            (2, 300),
+            (3, 3010),
+            (4, 4010),
+            (310, 1196),
+        ],
+    );
+}
+
+#[test]
+fn nested_call() {
+    // locals are checked to start at 0 by filling the stack with garbage
+    let garbage: Vec<_> = (261..300).map(|address| (address, -1)).collect();
+    let emulator = run("NestedCall", true, &garbage);
+
+    assert_ram(
+        &emulator,
+        &[
+            (0, 261),
+            (1, 261),
+            (2, 256),
+            (3, 4000),
+            (4, 5000),
+            (5, 135),
+            (6, 246),
+        ],
+    );
+}
+
+#[test]
+fn fibonacci_element() {
+    let emulator = run("FibonacciElement", true, &[]);
+    assert_ram(&emulator, &[(0, 262), (261, 3)]);
+}
+
+#[test]
+fn statics_test() {
+    let emulator = run("StaticsTest", true, &[]);
+    assert_ram(&emulator, &[(0, 263), (261, -2), (262, 8)]);
+}
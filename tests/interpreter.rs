@@ -0,0 +1,135 @@
+use vm_translator::{
+    bootstrap::Bootstrap, emulator::StopReason, interpreter::Interpreter, parser::Parser,
+};
+
+fn load(sources: &[(&str, &str)]) -> Vec<Parser> {
+    sources
+        .iter()
+        .map(|(name, source)| Parser::new(&format!("{}.vm", name), source.as_bytes()))
+        .collect()
+}
+
+fn interpreter<'a>(sources: &[(&str, &str)], parsers: &'a [Parser]) -> Interpreter<'a> {
+    let files = sources
+        .iter()
+        .zip(parsers)
+        .map(|((name, _), parser)| {
+            let (instructions, diagnostics) = parser.parse();
+            assert!(diagnostics.is_empty(), "{}", diagnostics);
+            let op_codes = instructions.into_iter().map(|i| i.op_code).collect();
+            (name.to_string(), op_codes)
+        })
+        .collect();
+
+    Interpreter::new(files).unwrap()
+}
+
+#[test]
+fn executes_segments_and_arithmetic() {
+    let sources = [(
+        "Main",
+        "push constant 10
+        pop pointer 0
+        push constant 21
+        push constant 22
+        pop this 2
+        pop temp 6
+        push this 2
+        push temp 6
+        sub
+        push constant 0
+        not
+        and",
+    )];
+    let parsers = load(&sources);
+    let mut interpreter = interpreter(&sources, &parsers);
+    interpreter.set_ram(0, 256);
+
+    assert_eq!(interpreter.run(100).unwrap(), StopReason::Halted);
+    assert_eq!(interpreter.ram(0), 257);
+    assert_eq!(interpreter.ram(256), 1);
+    assert_eq!(interpreter.ram(3), 10);
+    assert_eq!(interpreter.ram(12), 22);
+    assert_eq!(interpreter.ram(11), 21);
+}
+
+#[test]
+fn calls_functions_with_frames_and_per_file_statics() {
+    let sources = [
+        (
+            "Sys",
+            "function Sys.init 0
+            push constant 6
+            push constant 7
+            call Math.mul 2
+            pop static 0
+            label HALT
+            goto HALT",
+        ),
+        (
+            "Math",
+            "function Math.mul 1
+            label LOOP
+            push argument 1
+            if-goto BODY
+            push local 0
+            push constant 1
+            pop static 0
+            return
+            label BODY
+            push local 0
+            push argument 0
+            add
+            pop local 0
+            push argument 1
+            push constant 1
+            sub
+            pop argument 1
+            goto LOOP",
+        ),
+    ];
+    let parsers = load(&sources);
+    let mut interpreter = interpreter(&sources, &parsers);
+    interpreter.bootstrap(&Bootstrap::default()).unwrap();
+
+    assert_eq!(interpreter.run(1000).unwrap(), StopReason::Halted);
+    assert_eq!(interpreter.ram(0), 261);
+    assert_eq!(interpreter.return_address_slots(), [256]);
+
+    let sys_static = interpreter.static_address("Sys", 0).unwrap();
+    let math_static = interpreter.static_address("Math", 0).unwrap();
+    assert_ne!(sys_static, math_static);
+    assert_eq!(interpreter.ram(sys_static), 42);
+    assert_eq!(interpreter.ram(math_static), 1);
+}
+
+#[test]
+fn rejects_undefined_call_targets() {
+    let sources = [("Main", "call Missing.function 0")];
+    let parsers = load(&sources);
+    let (instructions, _) = parsers[0].parse();
+    let op_codes = instructions.into_iter().map(|i| i.op_code).collect();
+
+    assert!(Interpreter::new(vec![("Main".to_owned(), op_codes)]).is_err());
+}
+
+#[test]
+fn rejects_fixed_segment_indexes_past_the_segment() {
+    for source in ["push temp 8", "pop temp 70000", "push pointer 2"] {
+        let sources = [("Main", source)];
+        let parsers = load(&sources);
+        let (instructions, _) = parsers[0].parse();
+        let op_codes = instructions.into_iter().map(|i| i.op_code).collect();
+        let mut interpreter = Interpreter::new(vec![("Main".to_owned(), op_codes)]).unwrap();
+        interpreter.set_ram(0, 256);
+        interpreter.set_ram(256, 1);
+
+        let error = interpreter.step().unwrap_err();
+        assert!(
+            error.to_string().contains("is outside the segment"),
+            "{}: {}",
+            source,
+            error
+        );
+    }
+}
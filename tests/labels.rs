@@ -0,0 +1,126 @@
+mod common;
+
+use vm_translator::{bootstrap::Bootstrap, emulator::StopReason};
+
+// two `return` paths, each followed by more labelled code
+const MAIN: &str = "
+function Main.abs 0
+push argument 0
+push constant 0
+lt
+if-goto NEGATIVE
+push argument 0
+return
+label NEGATIVE
+push argument 0
+neg
+return
+
+function Main.sign 0
+push argument 0
+push constant 0
+lt
+if-goto NEGATIVE
+push constant 0
+push argument 0
+lt
+if-goto POSITIVE
+push constant 0
+return
+label NEGATIVE
+push constant 1
+neg
+return
+label POSITIVE
+push constant 1
+return
+";
+
+const SYS: &str = "
+function Sys.init 0
+push constant 5
+neg
+call Main.abs 1
+pop static 0
+push constant 7
+call Main.abs 1
+pop static 1
+push constant 9
+neg
+call Main.sign 1
+pop static 2
+push constant 9
+call Main.sign 1
+pop static 3
+push constant 0
+call Main.sign 1
+pop static 4
+label END
+goto END
+";
+
+#[test]
+fn labels_after_an_early_return_stay_in_the_function() {
+    let asm = common::translate(&[("Main", MAIN)], None);
+
+    assert!(asm.contains("(Main.abs$NEGATIVE)"));
+    assert!(asm.contains("(Main.sign$NEGATIVE)"));
+    assert!(asm.contains("(Main.sign$POSITIVE)"));
+    assert!(!asm.contains("(Sys$"));
+}
+
+#[test]
+fn labels_before_any_function_are_scoped_to_the_file() {
+    let asm = common::translate(&[("Loop", "label START\ngoto START\n")], None);
+
+    assert!(asm.contains("(Loop$START)"));
+    assert!(asm.contains("@Loop$START"));
+}
+
+#[test]
+fn functions_with_several_returns_run_correctly() {
+    let bootstrap = Bootstrap::default();
+    let mut emulator = common::emulate(&[("Main", MAIN), ("Sys", SYS)], Some(&bootstrap));
+
+    assert_eq!(emulator.run(10_000), StopReason::Halted);
+    let results: Vec<_> = (0..5)
+        .map(|i| emulator.ram(emulator.symbol(&format!("Sys.{}", i)).unwrap()))
+        .collect();
+    assert_eq!(results, [5, 7, -1, 1, 0]);
+}
+
+// user labels named like the ones generated for comparisons and returns
+const LOOKALIKES: &str = "
+function Sys.init 0
+push constant 3
+push constant 5
+lt
+if-goto cmp.0
+push constant 0
+pop static 0
+goto ret.0
+label cmp.0
+push constant 1
+pop static 0
+label ret.0
+call Sys.answer 0
+pop static 1
+label cmp.0.end
+label cmp.0.ordered
+label END
+goto END
+
+function Sys.answer 0
+push constant 42
+return
+";
+
+#[test]
+fn generated_labels_never_collide_with_user_labels() {
+    let bootstrap = Bootstrap::default();
+    let mut emulator = common::emulate(&[("Sys", LOOKALIKES)], Some(&bootstrap));
+
+    assert_eq!(emulator.run(10_000), StopReason::Halted);
+    assert_eq!(emulator.ram(emulator.symbol("Sys.0").unwrap()), 1);
+    assert_eq!(emulator.ram(emulator.symbol("Sys.1").unwrap()), 42);
+}
@@ -0,0 +1,212 @@
+mod common;
+
+use std::{fs, path::Path};
+use vm_translator::{
+    bootstrap::Bootstrap,
+    diagnostic::{DiagnosticKind, Diagnostics},
+    emulator::{Emulator, StopReason},
+    translator::{SourceOptions, Translator, TranslatorOptions},
+};
+
+const SYS: &str = "function Sys.init 0
+push constant 6
+push constant 7
+call Math.multiply 2
+pop static 0
+label END
+goto END";
+
+const MATH: &str = "function Math.multiply 2
+push argument 1
+pop local 1
+label LOOP
+push local 1
+if-goto BODY
+push local 0
+return
+label BODY
+push local 0
+push argument 0
+call Math.add 2
+pop local 0
+push local 1
+push constant 1
+sub
+pop local 1
+goto LOOP
+function Math.add 0
+push argument 0
+push argument 1
+add
+return";
+
+const OUTPUT: &str = "function Output.println 0
+call Screen.missing 0
+return";
+
+fn path(dir: &Path, path: &str) -> String {
+    dir.join(path).to_str().unwrap().to_owned()
+}
+
+/// The namespaces of every source loaded for the inputs, in load order
+fn load(inputs: &[String], libraries: &[String]) -> anyhow::Result<Vec<String>> {
+    let options = SourceOptions {
+        libraries: libraries.to_vec(),
+        ..SourceOptions::default()
+    };
+    let inputs: Vec<&str> = inputs.iter().map(String::as_str).collect();
+    let sources = Translator::load_sources(&inputs, &options)?;
+    Ok(sources.into_iter().map(|source| source.namespace).collect())
+}
+
+#[test]
+fn pulls_in_only_the_library_files_that_are_called() {
+    let dir = common::write_dir(
+        "libraries-needed",
+        &[
+            ("app/Sys.vm", SYS),
+            ("os/Math.vm", MATH),
+            ("os/Output.vm", OUTPUT),
+        ],
+    );
+
+    assert_eq!(
+        load(&[path(&dir, "app")], &[path(&dir, "os")]).unwrap(),
+        ["Sys", "Math"]
+    );
+}
+
+#[test]
+fn follows_calls_made_by_library_files() {
+    let math = "function Math.multiply 0\ncall Memory.alloc 0\nreturn";
+    let memory = "function Memory.alloc 0\npush constant 0\nreturn";
+    let dir = common::write_dir(
+        "libraries-transitive",
+        &[
+            ("app/Sys.vm", SYS),
+            ("os/Math.vm", math),
+            ("vendor/Memory.vm", memory),
+            ("vendor/Output.vm", OUTPUT),
+        ],
+    );
+
+    assert_eq!(
+        load(
+            &[path(&dir, "app")],
+            &[path(&dir, "os"), path(&dir, "vendor")]
+        )
+        .unwrap(),
+        ["Sys", "Math", "Memory"]
+    );
+}
+
+#[test]
+fn the_first_library_defining_a_function_wins() {
+    let dir = common::write_dir(
+        "libraries-order",
+        &[
+            ("app/Sys.vm", SYS),
+            ("os/Math.vm", MATH),
+            ("vendor/Math.vm", MATH),
+        ],
+    );
+
+    let loaded = load(
+        &[path(&dir, "app")],
+        &[path(&dir, "vendor"), path(&dir, "os")],
+    )
+    .unwrap();
+    assert_eq!(loaded, ["Sys", "Math"]);
+}
+
+#[test]
+fn inputs_load_in_order_and_may_not_share_a_namespace() {
+    let dir = common::write_dir(
+        "libraries-inputs",
+        &[
+            ("app/Sys.vm", SYS),
+            ("os/Math.vm", MATH),
+            ("more/Sys.vm", SYS),
+        ],
+    );
+
+    assert_eq!(
+        load(&[path(&dir, "os"), path(&dir, "app")], &[]).unwrap(),
+        ["Math", "Sys"]
+    );
+
+    let err = load(&[path(&dir, "app"), path(&dir, "more")], &[]).unwrap_err();
+    assert!(
+        err.to_string().contains("share the static namespace `Sys`"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn translates_inputs_with_library_functions() {
+    let dir = common::write_dir(
+        "libraries-translate",
+        &[
+            ("app/Sys.vm", SYS),
+            ("os/Math.vm", MATH),
+            ("os/Output.vm", OUTPUT),
+        ],
+    );
+    let output = dir.join("Out.asm");
+
+    let options = TranslatorOptions {
+        bootstrap: Some(Bootstrap::default()),
+        sources: SourceOptions {
+            libraries: vec![path(&dir, "os")],
+            ..SourceOptions::default()
+        },
+        ..TranslatorOptions::default()
+    };
+    Translator::new(
+        path(&dir, "app"),
+        output.to_str().unwrap().to_owned(),
+        options,
+    )
+    .translate()
+    .unwrap();
+
+    let asm = fs::read_to_string(output).unwrap();
+    assert!(!asm.contains("Output.println"));
+    let mut emulator = Emulator::from_asm(&asm).unwrap();
+    assert_eq!(emulator.run(100_000), StopReason::Halted);
+    assert_eq!(emulator.ram(16), 42);
+}
+
+#[test]
+fn calls_no_library_defines_are_still_reported() {
+    let dir = common::write_dir(
+        "libraries-unresolved",
+        &[("app/Main.vm", OUTPUT), ("os/Math.vm", MATH)],
+    );
+    let output = dir.join("Out.asm");
+
+    let options = TranslatorOptions {
+        sources: SourceOptions {
+            libraries: vec![path(&dir, "os")],
+            ..SourceOptions::default()
+        },
+        ..TranslatorOptions::default()
+    };
+    let err = Translator::new(
+        path(&dir, "app"),
+        output.to_str().unwrap().to_owned(),
+        options,
+    )
+    .translate()
+    .unwrap_err();
+
+    let diagnostics = err.downcast::<Diagnostics>().unwrap();
+    let kinds: Vec<_> = diagnostics.iter().map(|d| d.kind.clone()).collect();
+    assert_eq!(
+        kinds,
+        [DiagnosticKind::UndefinedFunction(
+            "Screen.missing".to_owned()
+        )]
+    );
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use vm_translator::{diagnostic::DiagnosticKind, translator::Translator};
+
+/// The `(file, line, column, kind)` of every diagnostic for the sources
+fn link(sources: &[(&str, &str)]) -> Vec<(String, usize, usize, DiagnosticKind)> {
+    let sources = common::sources(sources);
+    let (_, diagnostics) = Translator::parse_sources(&sources);
+
+    diagnostics
+        .iter()
+        .map(|d| (d.filename.clone(), d.line, d.column, d.kind.clone()))
+        .collect()
+}
+
+#[test]
+fn accepts_a_fully_resolved_program() {
+    let main = "function Main.main 0\nlabel LOOP\ncall Util.id 0\nif-goto LOOP\nreturn";
+    let util = "function Util.id 0\npush constant 0\nreturn";
+
+    assert!(link(&[("Main", main), ("Util", util)]).is_empty());
+}
+
+#[test]
+fn reports_calls_to_undefined_functions() {
+    let main = "function Main.main 0\n  call Main.missing 2\nreturn";
+
+    assert_eq!(
+        link(&[("Main", main)]),
+        [(
+            "Main.vm".to_owned(),
+            2,
+            8,
+            DiagnosticKind::UndefinedFunction("Main.missing".to_owned())
+        )]
+    );
+}
+
+#[test]
+fn reports_functions_defined_in_two_files() {
+    let first = "function Util.id 0\nreturn";
+    let second = "// again\nfunction Util.id 0\nreturn";
+
+    assert_eq!(
+        link(&[("First", first), ("Second", second)]),
+        [(
+            "Second.vm".to_owned(),
+            2,
+            10,
+            DiagnosticKind::DuplicateFunction {
+                name: "Util.id".to_owned(),
+                previous: "First.vm:1".to_owned(),
+            }
+        )]
+    );
+}
+
+#[test]
+fn labels_are_only_visible_in_their_function() {
+    let main = "function Main.a 0\nlabel LOOP\nreturn\nfunction Main.b 0\ngoto LOOP";
+
+    assert_eq!(
+        link(&[("Main", main)]),
+        [(
+            "Main.vm".to_owned(),
+            5,
+            6,
+            DiagnosticKind::UndefinedLabel {
+                label: "LOOP".to_owned(),
+                scope: "Main.b".to_owned(),
+            }
+        )]
+    );
+}
+
+#[test]
+fn labels_may_follow_the_jumps_to_them() {
+    let main = "function Main.a 0\ngoto END\nreturn\nlabel END\nreturn";
+    assert!(link(&[("Main", main)]).is_empty());
+}
+
+#[test]
+fn reports_labels_declared_twice_in_a_scope() {
+    // code before any function forms its own scope
+    let main = "label TOP\nlabel TOP\nfunction Main.a 0\nlabel TOP\nreturn";
+
+    assert_eq!(
+        link(&[("Main", main)]),
+        [(
+            "Main.vm".to_owned(),
+            2,
+            7,
+            DiagnosticKind::DuplicateLabel {
+                label: "TOP".to_owned(),
+                scope: "Main.vm".to_owned(),
+                previous_line: 1,
+            }
+        )]
+    );
+}
+
+#[test]
+fn formats_link_errors_like_other_diagnostics() {
+    let sources = common::sources(&[("Main", "function Main.main 0\ncall Foo.bar 2\nreturn")]);
+    let (_, diagnostics) = Translator::parse_sources(&sources);
+
+    assert_eq!(
+        diagnostics.to_string(),
+        "error: call to undefined function `Foo.bar`\n --> Main.vm:2:6\n  |\n2 | call Foo.bar 2\n  |      ^^^^^^^\n"
+    );
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use std::{fs, path::Path};
+use vm_translator::translator::{SourceOptions, Translator, TranslatorOptions};
+
+const STATICS: &str = "push constant 7\npop static 0";
+
+/// The static namespace of every source loaded from `input`
+fn namespaces(input: &Path, options: &SourceOptions) -> anyhow::Result<Vec<String>> {
+    let sources = Translator::load_sources(&[input.to_str().unwrap()], options)?;
+    Ok(sources.into_iter().map(|source| source.namespace).collect())
+}
+
+#[test]
+fn namespaces_are_file_stems() {
+    let dir = common::write_dir(
+        "namespace-stems",
+        &[
+            ("Foo.vm.vm", STATICS),
+            ("my.vm_utils.vm", STATICS),
+            ("vm.vm", STATICS),
+        ],
+    );
+
+    assert_eq!(
+        namespaces(&dir, &SourceOptions::default()).unwrap(),
+        ["Foo.vm", "my.vm_utils", "vm"]
+    );
+}
+
+#[test]
+fn statics_use_the_file_stem() {
+    let dir = common::write_dir("namespace-statics", &[("Foo.vm.vm", STATICS)]);
+    let output = dir.join("Out.asm");
+
+    Translator::new(
+        dir.join("Foo.vm.vm").to_str().unwrap().to_owned(),
+        output.to_str().unwrap().to_owned(),
+        TranslatorOptions::default(),
+    )
+    .translate()
+    .unwrap();
+
+    let asm = fs::read_to_string(output).unwrap();
+    assert!(
+        asm.lines().any(|line| line.trim() == "@Foo.vm.0"),
+        "{}",
+        asm
+    );
+}
+
+#[test]
+fn rejects_namespaces_that_are_not_vm_symbols() {
+    for (name, file) in [
+        ("digit", "3d.vm"),
+        ("dash", "my-file.vm"),
+        ("dollar", "Sys$.vm"),
+    ] {
+        let dir = common::write_dir(&format!("namespace-illegal-{}", name), &[(file, STATICS)]);
+
+        let err = namespaces(&dir, &SourceOptions::default()).unwrap_err();
+        assert!(
+            err.to_string().contains("is not a legal VM symbol"),
+            "{}",
+            err
+        );
+    }
+}
+
+#[test]
+fn prefixes_namespaces_with_the_directory_name() {
+    let dir = common::write_dir("namespace-prefix", &[("lib/Math.vm", STATICS)]);
+    let options = SourceOptions {
+        prefix_namespaces: true,
+        ..SourceOptions::default()
+    };
+
+    assert_eq!(
+        namespaces(&dir.join("lib"), &options).unwrap(),
+        ["lib.Math"]
+    );
+    assert_eq!(
+        namespaces(&dir.join("lib/Math.vm"), &options).unwrap(),
+        ["lib.Math"]
+    );
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use std::{
+    env, fs,
+    io::Write,
+    process::{Command, Stdio},
+};
+use vm_translator::translator::{Emit, Translator, TranslatorOptions};
+
+const MAIN: &str = "push constant 7\npop static 0";
+
+#[test]
+fn default_output_follows_the_course_convention() {
+    let dir = common::write_dir("output-default", &[("Main.vm", MAIN)]);
+    fs::create_dir_all(dir.join("my.project")).unwrap();
+    let path = |path: &str| dir.join(path).to_str().unwrap().to_owned();
+
+    assert_eq!(
+        Translator::default_output(&path("Main.vm"), Emit::Asm).unwrap(),
+        path("Main.asm")
+    );
+    assert_eq!(
+        Translator::default_output(&path("Main.vm"), Emit::Hack).unwrap(),
+        path("Main.hack")
+    );
+    let dir_name = dir.file_name().unwrap().to_str().unwrap();
+    assert_eq!(
+        Translator::default_output(&path(""), Emit::Asm).unwrap(),
+        path(&format!("{}.asm", dir_name))
+    );
+    assert_eq!(
+        Translator::default_output(&path("my.project"), Emit::Asm).unwrap(),
+        path("my.project/my.project.asm")
+    );
+    assert_eq!(Translator::default_output("-", Emit::Asm).unwrap(), "-");
+}
+
+#[cfg(unix)]
+#[test]
+fn default_output_rejects_directories_without_a_utf8_name() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    let dir = common::write_dir("output-non-utf8", &[]);
+    let target = dir.join(OsStr::from_bytes(b"\xffbad"));
+    fs::create_dir_all(&target).unwrap();
+    std::os::unix::fs::symlink(&target, dir.join("link")).unwrap();
+
+    let input = dir.join("link").to_str().unwrap().to_owned();
+    let err = Translator::default_output(&input, Emit::Asm).unwrap_err();
+    assert!(err.to_string().contains("is not valid UTF-8"), "{}", err);
+}
+
+#[test]
+fn a_failed_translation_leaves_the_output_alone() {
+    let dir = common::write_dir("output-failed", &[("Main.vm", "push constant 1\nbogus")]);
+    let output = dir.join("Main.asm");
+    fs::write(&output, "// previous output").unwrap();
+
+    let result = Translator::new(
+        dir.to_str().unwrap().to_owned(),
+        output.to_str().unwrap().to_owned(),
+        TranslatorOptions::default(),
+    )
+    .translate();
+
+    assert!(result.is_err());
+    assert_eq!(fs::read_to_string(&output).unwrap(), "// previous output");
+}
+
+#[test]
+fn replaces_the_output_without_leaving_temporary_files() {
+    let dir = common::write_dir("output-replaced", &[("Main.vm", MAIN)]);
+    let output = dir.join("Main.asm");
+    fs::write(&output, "// previous output").unwrap();
+
+    Translator::new(
+        dir.to_str().unwrap().to_owned(),
+        output.to_str().unwrap().to_owned(),
+        TranslatorOptions::default(),
+    )
+    .translate()
+    .unwrap();
+
+    assert!(fs::read_to_string(&output).unwrap().contains("@Main.0"));
+    let mut files: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    files.sort();
+    assert_eq!(files, ["Main.asm", "Main.vm"]);
+}
+
+#[test]
+fn translates_from_stdin_to_stdout() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_vm_translator"))
+        .args(["-i", "-", "-o", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(MAIN.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    let asm = String::from_utf8(output.stdout).unwrap();
+    assert!(asm.lines().any(|line| line.trim() == "@Stdin.0"), "{}", asm);
+}
+
+#[test]
+fn writes_next_to_the_input_by_default() {
+    let dir = common::write_dir("output-cli-default", &[("Main.vm", MAIN)]);
+
+    let status = Command::new(env!("CARGO_BIN_EXE_vm_translator"))
+        .args(["-i", dir.join("Main.vm").to_str().unwrap()])
+        .status()
+        .unwrap();
+
+    assert!(status.success());
+    assert!(fs::read_to_string(dir.join("Main.asm"))
+        .unwrap()
+        .contains("@Main.0"));
+}
@@ -0,0 +1,248 @@
+use vm_translator::{
+    diagnostic::{Diagnostic, DiagnosticKind},
+    op_code::OpCode,
+    parser::Parser,
+};
+
+/// The only diagnostic reported for a one-line source
+fn error(source: &str) -> Diagnostic {
+    let parser = Parser::new("Test.vm", source.as_bytes());
+    let (instructions, diagnostics) = parser.parse();
+    assert!(instructions.is_empty());
+
+    let diagnostics: Vec<_> = diagnostics.iter().cloned().collect();
+    assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+    diagnostics[0].clone()
+}
+
+/// The `(kind, line, column, len)` of the diagnostic for a source
+fn located(source: &str) -> (DiagnosticKind, usize, usize, usize) {
+    let diagnostic = error(source);
+    (
+        diagnostic.kind,
+        diagnostic.line,
+        diagnostic.column,
+        diagnostic.len,
+    )
+}
+
+#[test]
+fn parses_every_command() {
+    let source = "push constant 7\npop local 0\nadd\nsub\nneg\neq\ngt\nlt\nand\nor\nnot
+label LOOP\ngoto LOOP\nif-goto LOOP\nfunction Main.main 2\ncall Main.main 0\nreturn";
+    let parser = Parser::new("Test.vm", source.as_bytes());
+    let (instructions, diagnostics) = parser.parse();
+
+    assert!(diagnostics.is_empty(), "{}", diagnostics);
+    assert_eq!(instructions.len(), 17);
+    assert_eq!(
+        instructions[14].op_code,
+        OpCode::Function {
+            func_name: "Main.main",
+            num_locals: 2
+        }
+    );
+    assert_eq!(instructions[16].line, 17);
+}
+
+#[test]
+fn reports_unknown_commands() {
+    assert_eq!(
+        located("  mul // multiply"),
+        (DiagnosticKind::UnknownCommand("mul".to_owned()), 1, 3, 3)
+    );
+}
+
+#[test]
+fn reports_missing_operands_just_past_the_instruction() {
+    let missing = |command: &str, operand| DiagnosticKind::MissingOperand {
+        command: command.to_owned(),
+        operand,
+    };
+
+    assert_eq!(located("push"), (missing("push", "segment"), 1, 5, 1));
+    assert_eq!(located("pop local  "), (missing("pop", "index"), 1, 10, 1));
+    assert_eq!(located("goto"), (missing("goto", "label"), 1, 5, 1));
+    assert_eq!(
+        located("\tcall Foo.bar"),
+        (missing("call", "argument count"), 1, 14, 1)
+    );
+}
+
+#[test]
+fn reports_non_numeric_indices() {
+    assert_eq!(
+        located("push constant x1"),
+        (
+            DiagnosticKind::NonNumericIndex {
+                operand: "index",
+                found: "x1".to_owned()
+            },
+            1,
+            15,
+            2
+        )
+    );
+    assert_eq!(
+        located("function Main.main -1"),
+        (
+            DiagnosticKind::NonNumericIndex {
+                operand: "local count",
+                found: "-1".to_owned()
+            },
+            1,
+            20,
+            2
+        )
+    );
+}
+
+#[test]
+fn reports_extra_tokens() {
+    assert_eq!(
+        located("add 1 2"),
+        (
+            DiagnosticKind::ExtraTokens {
+                command: "add".to_owned(),
+                found: "1".to_owned()
+            },
+            1,
+            5,
+            1
+        )
+    );
+    assert_eq!(
+        located("label END again"),
+        (
+            DiagnosticKind::ExtraTokens {
+                command: "label".to_owned(),
+                found: "again".to_owned()
+            },
+            1,
+            11,
+            5
+        )
+    );
+}
+
+#[test]
+fn reports_labels_and_function_names_that_are_not_symbols() {
+    for (source, found, operand, column) in [
+        ("label a$b", "a$b", "label", 7),
+        ("goto 1st", "1st", "label", 6),
+        ("if-goto x-y", "x-y", "label", 9),
+        ("function Main.f$ 0", "Main.f$", "function name", 10),
+        ("call Main.$$CALL 0", "Main.$$CALL", "function name", 6),
+    ] {
+        assert_eq!(
+            located(source),
+            (
+                DiagnosticKind::InvalidSymbol {
+                    operand,
+                    found: found.to_owned()
+                },
+                1,
+                column,
+                found.len()
+            ),
+            "{}",
+            source
+        );
+    }
+}
+
+#[test]
+fn reports_counts_out_of_range() {
+    assert_eq!(
+        located("call Foo.bar 256"),
+        (
+            DiagnosticKind::CountOutOfRange {
+                operand: "argument count",
+                found: "256".to_owned(),
+                max: 255
+            },
+            1,
+            14,
+            3
+        )
+    );
+    assert_eq!(
+        located("push constant 99999999999"),
+        (
+            DiagnosticKind::CountOutOfRange {
+                operand: "index",
+                found: "99999999999".to_owned(),
+                max: u32::MAX
+            },
+            1,
+            15,
+            11
+        )
+    );
+}
+
+#[test]
+fn columns_count_characters_not_bytes() {
+    let diagnostic = error("push é 1");
+
+    assert_eq!(
+        diagnostic.kind,
+        DiagnosticKind::UnknownSegment("é".to_owned())
+    );
+    assert_eq!((diagnostic.column, diagnostic.len), (6, 1));
+}
+
+#[test]
+fn displays_diagnostics_compiler_style() {
+    let source = "push constant 1\n\n\n\n\n\n\n\n\n  push lokal 3";
+    let parser = Parser::new("Test.vm", source.as_bytes());
+    let (_, diagnostics) = parser.parse();
+
+    assert_eq!(
+        diagnostics.to_string(),
+        "error: unknown segment `lokal`
+  --> Test.vm:10:8
+   |
+10 |   push lokal 3
+   |        ^^^^^
+"
+    );
+}
+
+#[test]
+fn recovers_from_bad_lines() {
+    let source = "push constant 1
+mul
+push constant 2
+pop nowhere 0
+// just a comment
+add
+push constant x
+goto
+label END extra
+goto END";
+    let parser = Parser::new("Test.vm", source.as_bytes());
+    let (instructions, diagnostics) = parser.parse();
+
+    let lines: Vec<_> = diagnostics.iter().map(|d| d.line).collect();
+    assert_eq!(lines, [2, 4, 7, 8, 9]);
+
+    let parsed: Vec<_> = instructions.iter().map(|i| (i.line, i.source)).collect();
+    assert_eq!(
+        parsed,
+        [
+            (1, "push constant 1"),
+            (3, "push constant 2"),
+            (6, "add"),
+            (10, "goto END")
+        ]
+    );
+}
+
+#[test]
+fn rejects_unknown_segments_at_parse_time() {
+    assert_eq!(
+        located("push lokal 3"),
+        (DiagnosticKind::UnknownSegment("lokal".to_owned()), 1, 6, 5)
+    );
+}
@@ -0,0 +1,160 @@
+mod common;
+
+use vm_translator::{
+    bootstrap::Bootstrap,
+    emulator::{Emulator, StopReason},
+    peephole::{self, Line},
+    translator::TranslatorOptions,
+};
+
+fn lines(asm: &str) -> Vec<Line> {
+    asm.lines()
+        .map(|line| match line.strip_prefix("// ") {
+            Some(comment) => Line::Comment(comment.to_owned()),
+            None if line.starts_with('(') => Line::Label(line.to_owned()),
+            None => Line::Instruction(line.to_owned()),
+        })
+        .collect()
+}
+
+fn optimize(asm: &str, level: u8) -> Vec<Line> {
+    let mut lines = lines(asm);
+    peephole::optimize(&mut lines, level);
+    lines
+}
+
+/// Translates a single file at the given optimisation level and runs it
+fn run(source: &str, opt_level: u8, bootstrap: Option<&Bootstrap>, ram: &[(u16, i16)]) -> Emulator {
+    let options = TranslatorOptions {
+        bootstrap: bootstrap.cloned(),
+        opt_level,
+        ..TranslatorOptions::default()
+    };
+    let asm = common::translate_with(&[("Main", source)], &options);
+
+    let mut emulator = Emulator::from_asm(&asm).unwrap();
+    for &(address, value) in ram {
+        emulator.set_ram(address, value);
+    }
+    assert_eq!(emulator.run(100_000), StopReason::Halted);
+    emulator
+}
+
+#[test]
+fn level_zero_leaves_the_code_alone() {
+    let asm = "@SP\nAM=M-1\nD=M\nM=0\n@SP\n@SP";
+    assert_eq!(optimize(asm, 0), lines(asm));
+}
+
+#[test]
+fn removes_the_clear_after_a_pop() {
+    assert_eq!(
+        optimize("@SP\nAM=M-1\n// comment\nD=M\nM=0\n@R13", 1),
+        lines("@SP\nAM=M-1\n// comment\nD=M\n@R13")
+    );
+}
+
+#[test]
+fn removes_loads_of_the_value_already_in_a() {
+    assert_eq!(
+        optimize("@R6\nM=D\n@R6\nD=M\n@R6\nA=M\n@R6", 1),
+        lines("@R6\nM=D\nD=M\nA=M\n@R6")
+    );
+}
+
+#[test]
+fn keeps_loads_after_labels_and_jumps() {
+    let asm = "@LOOP\nD;JNE\n@LOOP\n0;JMP\n@LOOP\n(LOOP)\n@LOOP";
+    assert_eq!(
+        optimize(asm, 1),
+        lines("@LOOP\nD;JNE\n0;JMP\n@LOOP\n(LOOP)\n@LOOP")
+    );
+}
+
+#[test]
+fn fuses_a_push_into_the_following_pop() {
+    let push_pop = "@7\nD=A\n@SP\nA=M\nM=D\n@SP\nM=M+1\n\
+                    @5\nD=A\n@R13\nM=D\n@SP\nAM=M-1\nD=M\nM=0\n@R13\nA=M\nM=D";
+
+    assert_eq!(optimize(push_pop, 2), lines("@7\nD=A\n@5\nM=D"));
+    // without -O2 only the clear goes
+    assert_eq!(optimize(push_pop, 1).len(), lines(push_pop).len() - 1);
+}
+
+#[test]
+fn does_not_fuse_across_labels() {
+    let asm = "@SP\nA=M\nM=D\n@SP\nM=M+1\n(TARGET)\n\
+               @5\nD=A\n@R13\nM=D\n@SP\nAM=M-1\nD=M\n@R13\nA=M\nM=D";
+    assert_eq!(optimize(asm, 2), lines(asm));
+}
+
+const SEGMENTS: &str = "
+push constant 10
+pop local 0
+push constant 21
+push constant 22
+pop argument 2
+pop argument 1
+push constant 36
+pop this 6
+push constant 510
+pop temp 6
+push local 0
+pop static 3
+push static 3
+push argument 1
+add
+push this 6
+sub
+push temp 6
+lt
+push constant 5
+push constant 5
+eq
+";
+
+#[test]
+fn optimised_code_computes_the_same_results() {
+    let ram = [(0, 256), (1, 300), (2, 400), (3, 3000), (4, 3010)];
+    let expected = run(SEGMENTS, 0, None, &ram);
+
+    for level in 1..=2 {
+        let emulator = run(SEGMENTS, level, None, &ram);
+        assert!(emulator.cycles() < expected.cycles());
+
+        // R13-R15 are scratch registers and may differ
+        assert_eq!(emulator.ram_slice(0, 13), expected.ram_slice(0, 13));
+        assert_eq!(emulator.ram_slice(256, 2), expected.ram_slice(256, 2));
+        for address in [300, 401, 402, 3006] {
+            assert_eq!(emulator.ram(address), expected.ram(address));
+        }
+        assert_eq!(
+            emulator.ram(emulator.symbol("Main.3").unwrap()),
+            expected.ram(expected.symbol("Main.3").unwrap())
+        );
+    }
+}
+
+#[test]
+fn optimised_calls_and_returns_still_work() {
+    let source = "
+function Sys.init 0
+push constant 3
+push constant 4
+call Main.add 2
+pop static 0
+label END
+goto END
+function Main.add 1
+push argument 0
+pop local 0
+push local 0
+push argument 1
+add
+return
+";
+    for level in 0..=2 {
+        let emulator = run(source, level, Some(&Bootstrap::default()), &[]);
+        assert_eq!(emulator.ram(emulator.symbol("Main.0").unwrap()), 7);
+    }
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use vm_translator::{
+    bootstrap::Bootstrap,
+    emulator::{Emulator, StopReason},
+    translator::TranslatorOptions,
+};
+
+// fills every temp register, then checks them after calls and returns
+const SYS: &str = "
+function Sys.init 0
+push constant 10
+pop temp 0
+push constant 11
+pop temp 1
+push constant 12
+pop temp 2
+push constant 13
+pop temp 3
+push constant 14
+pop temp 4
+push constant 15
+pop temp 5
+push constant 16
+pop temp 6
+push constant 17
+pop temp 7
+push constant 5
+call Sys.outer 1
+pop static 0
+label END
+goto END
+
+function Sys.outer 2
+push argument 0
+pop local 1
+push local 1
+call Sys.inner 1
+return
+
+function Sys.inner 1
+push argument 0
+push constant 1
+add
+pop local 0
+push local 0
+return
+";
+
+// every op code that touches scratch registers, without any temp access
+const NO_TEMP: &str = "
+function Main.main 1
+push constant 1
+pop local 0
+push local 0
+pop static 0
+push local 0
+pop pointer 1
+push local 0
+call Main.id 1
+return
+function Main.id 0
+push argument 0
+return
+";
+
+fn translate(source: &str, shared_routines: bool, opt_level: u8) -> String {
+    let options = TranslatorOptions {
+        bootstrap: Some(Bootstrap::default()),
+        shared_routines,
+        opt_level,
+        ..TranslatorOptions::default()
+    };
+    common::translate_with(&[("Sys", source)], &options)
+}
+
+#[test]
+fn temp_survives_calls_and_returns() {
+    for shared_routines in [false, true] {
+        for opt_level in 0..=2 {
+            let asm = translate(SYS, shared_routines, opt_level);
+            let mut emulator = Emulator::from_asm(&asm).unwrap();
+
+            assert_eq!(emulator.run(10_000), StopReason::Halted);
+            assert_eq!(emulator.ram(emulator.symbol("Sys.0").unwrap()), 6);
+            assert_eq!(
+                emulator.ram_slice(5, 8),
+                [10, 11, 12, 13, 14, 15, 16, 17],
+                "shared routines: {}, -O{}",
+                shared_routines,
+                opt_level
+            );
+        }
+    }
+}
+
+#[test]
+fn generated_code_only_uses_r13_to_r15() {
+    for shared_routines in [false, true] {
+        for opt_level in 0..=2 {
+            let asm = translate(NO_TEMP, shared_routines, opt_level);
+
+            let registers: Vec<u16> = asm
+                .lines()
+                .filter_map(|line| line.trim().strip_prefix("@R")?.parse().ok())
+                .collect();
+
+            assert!(!registers.is_empty());
+            assert!(
+                registers.iter().all(|r| (13..=15).contains(r)),
+                "{:?}",
+                registers
+            );
+        }
+    }
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use vm_translator::{
+    assembler::Program,
+    bootstrap::Bootstrap,
+    emulator::{Emulator, StopReason},
+    translator::{Translation, TranslatorOptions},
+};
+
+/// Translates a fixture directory with the shared call and return routines
+fn translate(name: &str) -> (String, Translation) {
+    let options = TranslatorOptions {
+        bootstrap: Some(Bootstrap::default()),
+        shared_routines: true,
+        ..Default::default()
+    };
+    common::translate_fixture(name, options)
+}
+
+fn run(asm: &str) -> Emulator {
+    let mut emulator = Emulator::from_asm(asm).unwrap();
+    assert_eq!(emulator.run(1_000_000), StopReason::Halted);
+    emulator
+}
+
+#[test]
+fn reports_the_size_before_and_after() {
+    let (asm, translation) = translate("FibonacciElement");
+
+    let baseline = translation.baseline_instruction_count.unwrap();
+    assert!(translation.instruction_count < baseline);
+    assert_eq!(
+        translation.instruction_count as usize,
+        Program::assemble(&asm).unwrap().instructions.len()
+    );
+    assert_eq!(asm.matches("($$CALL)").count(), 1);
+    assert_eq!(asm.matches("($$RETURN)").count(), 1);
+}
+
+#[test]
+fn recursive_calls_run_through_the_shared_routines() {
+    let emulator = run(&translate("FibonacciElement").0);
+    assert_eq!(emulator.ram(0), 262);
+    assert_eq!(emulator.ram(261), 3);
+}
+
+#[test]
+fn frames_are_saved_and_restored_through_the_shared_routines() {
+    let emulator = run(&translate("NestedCall").0);
+    assert_eq!(
+        emulator.ram_slice(0, 7),
+        [261, 261, 256, 4000, 5000, 135, 246]
+    );
+}
+
+#[test]
+fn statics_survive_the_shared_routines() {
+    let emulator = run(&translate("StaticsTest").0);
+    assert_eq!(emulator.ram(0), 263);
+    assert_eq!(emulator.ram_slice(261, 2), [-2, 8]);
+}
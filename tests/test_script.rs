@@ -0,0 +1,138 @@
+mod common;
+
+use std::fs;
+use vm_translator::test_script::TestScript;
+
+const SIMPLE_ADD: &str = "push constant 7\npush constant 8\nadd\n";
+
+const SIMPLE_ADD_TST: &str = "
+// from the course's SimpleAdd.tst
+load SimpleAdd.asm,
+output-file SimpleAdd.out,
+compare-to SimpleAdd.cmp,
+output-list RAM[0]%D2.6.2 RAM[256]%D2.6.2;
+
+set RAM[0] 256,  /* initializes the stack pointer */
+
+repeat 60 {      // enough cycles to complete the execution
+  ticktock;
+}
+
+output;
+";
+
+#[test]
+fn runs_a_cpu_script_against_its_cmp_file() {
+    let asm = common::translate(&[("SimpleAdd", SIMPLE_ADD)], None);
+    let dir = common::write_dir(
+        "cpu",
+        &[
+            ("SimpleAdd.asm", &asm),
+            ("SimpleAdd.tst", SIMPLE_ADD_TST),
+            (
+                "SimpleAdd.cmp",
+                "|  RAM[0]  | RAM[256] |\n|     257  |      15  |\n",
+            ),
+        ],
+    );
+
+    let report = TestScript::load(&dir.join("SimpleAdd.tst"))
+        .unwrap()
+        .run()
+        .unwrap();
+
+    assert!(report.passed(), "{:?}", report.mismatches);
+    assert_eq!(
+        fs::read_to_string(dir.join("SimpleAdd.out")).unwrap(),
+        "|  RAM[0]  | RAM[256] |\n|     257  |      15  |\n"
+    );
+}
+
+#[test]
+fn reports_mismatching_rows() {
+    let asm = common::translate(&[("SimpleAdd", SIMPLE_ADD)], None);
+    let dir = common::write_dir(
+        "mismatch",
+        &[
+            ("SimpleAdd.asm", &asm),
+            ("SimpleAdd.tst", SIMPLE_ADD_TST),
+            ("SimpleAdd.cmp", "|RAM[0]|RAM[256]|\n|257|16|\n"),
+        ],
+    );
+
+    let report = TestScript::load(&dir.join("SimpleAdd.tst"))
+        .unwrap()
+        .run()
+        .unwrap();
+
+    assert_eq!(report.mismatches.len(), 1);
+    assert_eq!(report.mismatches[0].line, 2);
+    assert_eq!(report.mismatches[0].expected, "|257|16|");
+    assert_eq!(report.mismatches[0].actual, "|     257  |      15  |");
+}
+
+#[test]
+fn steps_vm_programs_with_while_loops() {
+    let dir = common::write_dir(
+        "vm",
+        &[
+            (
+                "Sys.vm",
+                "function Sys.init 0\npush constant 3\npush constant 4\ncall Sys.add 2\npop static 0\nlabel END\ngoto END\n\
+                 function Sys.add 0\npush argument 0\npush argument 1\nadd\nreturn\n",
+            ),
+            (
+                "Sys.tst",
+                "load,\noutput-list sp%D1.4.1 local%D1.4.1;\nset sp 261, set local 261,\n\
+                 while RAM[16] = 0 { vmstep; }\noutput;",
+            ),
+        ],
+    );
+
+    let report = TestScript::parse(&fs::read_to_string(dir.join("Sys.tst")).unwrap(), dir)
+        .unwrap()
+        .run()
+        .unwrap();
+
+    assert_eq!(report.output, ["|  sp  |local |", "|  261 |  261 |"]);
+}
+
+#[test]
+fn loads_vm_programs_inside_blocks() {
+    let dir = common::write_dir(
+        "vm-block-load",
+        &[
+            ("Ok.vm", "push constant 5\npop static 0\n"),
+            (
+                "Ok.tst",
+                "output-list RAM[16]%D1.2.1;\n\
+                 repeat 2 { load Ok.vm; set RAM[0] 256, vmstep, vmstep; output; }",
+            ),
+        ],
+    );
+
+    let report = TestScript::parse(&fs::read_to_string(dir.join("Ok.tst")).unwrap(), dir)
+        .unwrap()
+        .run()
+        .unwrap();
+
+    assert_eq!(report.output, ["|RAM[|", "|  5 |", "|  5 |"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn rejects_vm_programs_under_non_utf8_paths() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt, path::PathBuf};
+
+    let dir = PathBuf::from(OsStr::from_bytes(b"tests/\xffbad"));
+    let error = TestScript::parse("load Ok.vm;", dir)
+        .unwrap()
+        .run()
+        .unwrap_err();
+
+    assert!(
+        error.to_string().contains("is not valid UTF-8"),
+        "{}",
+        error
+    );
+}
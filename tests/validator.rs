@@ -0,0 +1,83 @@
+mod common;
+
+use vm_translator::{
+    diagnostic::DiagnosticKind, op_code::Segment, parser::Parser, translator::Translator,
+    validator::Validator,
+};
+
+/// The `(column, kind)` of every diagnostic the validator reports
+fn validate(source: &str) -> Vec<(usize, DiagnosticKind)> {
+    let parser = Parser::new("Test.vm", source.as_bytes());
+    let (instructions, diagnostics) = parser.parse();
+    assert!(diagnostics.is_empty(), "{}", diagnostics);
+
+    Validator::new("Test.vm")
+        .validate(&instructions)
+        .iter()
+        .map(|d| (d.column, d.kind.clone()))
+        .collect()
+}
+
+fn index_out_of_range(segment: Segment, index: u32, max: u32) -> DiagnosticKind {
+    DiagnosticKind::IndexOutOfRange {
+        segment,
+        index,
+        max,
+    }
+}
+
+#[test]
+fn rejects_pops_to_constant() {
+    assert_eq!(
+        validate("pop constant 5"),
+        [(5, DiagnosticKind::PopToConstant)]
+    );
+}
+
+#[test]
+fn checks_constants_fit_in_15_bits() {
+    assert!(validate("push constant 32767").is_empty());
+    assert_eq!(
+        validate("push constant 32768"),
+        [(
+            15,
+            DiagnosticKind::ConstantOutOfRange {
+                value: 32768,
+                max: 32767
+            }
+        )]
+    );
+}
+
+#[test]
+fn checks_fixed_segment_indices() {
+    assert!(validate("push pointer 1\npop temp 7\npush static 239").is_empty());
+
+    assert_eq!(
+        validate("push pointer 2"),
+        [(14, index_out_of_range(Segment::Pointer, 2, 1))]
+    );
+    assert_eq!(
+        validate("push temp 8"),
+        [(11, index_out_of_range(Segment::Temp, 8, 7))]
+    );
+    assert_eq!(
+        validate("push static 240"),
+        [(13, index_out_of_range(Segment::Static, 240, 239))]
+    );
+}
+
+#[test]
+fn leaves_based_segments_unchecked() {
+    assert!(validate("push local 1000\npop argument 9\npush this 300\npop that 40000").is_empty());
+}
+
+#[test]
+fn reports_diagnostics_in_line_order() {
+    let source = "push constant 1\nbogus\npush temp 9\npop local\npush pointer 5\nfoo";
+    let sources = common::sources(&[("Bad", source)]);
+    let (_, diagnostics) = Translator::parse_sources(&sources);
+
+    let lines: Vec<_> = diagnostics.iter().map(|d| d.line).collect();
+    assert_eq!(lines, [2, 3, 4, 5, 6]);
+}
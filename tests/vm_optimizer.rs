@@ -0,0 +1,148 @@
+use vm_translator::{
+    emulator::StopReason, interpreter::Interpreter, op_code::OpCode, parser::Parser, vm_optimizer,
+};
+
+fn interpret<'a>(op_codes: Vec<OpCode<'a>>, ram: &[(u16, i16)]) -> Interpreter<'a> {
+    let mut interpreter = Interpreter::new(vec![("Main".to_owned(), op_codes)]).unwrap();
+    interpreter.set_ram(0, 256);
+    for &(address, value) in ram {
+        interpreter.set_ram(address, value);
+    }
+
+    assert_eq!(interpreter.run(10_000).unwrap(), StopReason::Halted);
+    interpreter
+}
+
+/// Checks that `source` optimises to exactly `expected`, and that both
+/// leave the interpreter in the same state
+fn assert_optimizes(source: &str, expected: &str, ram: &[(u16, i16)]) {
+    let (source, expected) = (
+        Parser::new("Main.vm", source.as_bytes()),
+        Parser::new("Expected.vm", expected.as_bytes()),
+    );
+    let op_codes: Vec<_> = source.parse().0.into_iter().map(|i| i.op_code).collect();
+    let expected: Vec<_> = expected.parse().0.into_iter().map(|i| i.op_code).collect();
+
+    let optimized = vm_optimizer::optimize(&op_codes);
+    assert_eq!(optimized, expected);
+
+    let (before, after) = (interpret(op_codes, ram), interpret(optimized, ram));
+    assert_eq!(before.ram_slice(0, 256), after.ram_slice(0, 256));
+
+    let sp = before.ram(0);
+    assert_eq!(sp, after.ram(0));
+    let stack_len = (sp - 256) as usize;
+    assert_eq!(
+        before.ram_slice(256, stack_len),
+        after.ram_slice(256, stack_len)
+    );
+}
+
+#[test]
+fn folds_constant_arithmetic() {
+    assert_optimizes(
+        "push constant 2\npush constant 3\nadd\npush constant 4\nsub",
+        "push constant 1",
+        &[],
+    );
+}
+
+#[test]
+fn folds_with_16_bit_wraparound() {
+    assert_optimizes(
+        "push constant 32767\npush constant 1\nadd\npop temp 0\n\
+         push constant 0\npush constant 32767\nsub\npush constant 2\nsub\npop temp 1",
+        "push constant 32767\nnot\npop temp 0\npush constant 32767\npop temp 1",
+        &[],
+    );
+}
+
+#[test]
+fn folds_logic_and_unary_op_codes() {
+    assert_optimizes(
+        "push constant 0\nnot\npop temp 0\n\
+         push constant 12\npush constant 10\nand\npush constant 1\nor\npop temp 1\n\
+         push constant 7\nneg\nneg\npop temp 2",
+        "push constant 0\nnot\npop temp 0\npush constant 9\npop temp 1\npush constant 7\npop temp 2",
+        &[],
+    );
+}
+
+#[test]
+fn folds_comparisons() {
+    assert_optimizes(
+        "push constant 3\npush constant 4\nlt\npop temp 0\n\
+         push constant 4\npush constant 4\neq\npop temp 1\n\
+         push constant 3\npush constant 4\ngt\npop temp 2\n\
+         push constant 32767\npush constant 2\nneg\ngt\npop temp 3",
+        "push constant 1\nneg\npop temp 0\npush constant 1\nneg\npop temp 1\n\
+         push constant 0\npop temp 2\npush constant 1\nneg\npop temp 3",
+        &[],
+    );
+}
+
+#[test]
+fn drops_pushes_popped_straight_back() {
+    assert_optimizes(
+        "push local 1\npop local 1\npush static 2\npop static 2\npush local 1\npop local 2",
+        "push local 1\npop local 2",
+        &[(1, 300), (301, 17)],
+    );
+}
+
+#[test]
+fn drops_double_negation() {
+    assert_optimizes(
+        "push local 0\nnot\nnot\npush local 1\nneg\nneg\nadd",
+        "push local 0\npush local 1\nadd",
+        &[(1, 300), (300, 5), (301, -9)],
+    );
+}
+
+#[test]
+fn simplifies_conditions_of_if_goto() {
+    let source = "
+push local 0
+push local 1
+eq
+not
+if-goto DIFFERENT
+push local 0
+neg
+if-goto NONZERO
+push constant 0
+if-goto NEVER
+push constant 0
+not
+if-goto ALWAYS
+label NEVER
+label DIFFERENT
+label NONZERO
+label ALWAYS
+push constant 1
+";
+    let expected = "
+push local 0
+push local 1
+sub
+if-goto DIFFERENT
+push local 0
+if-goto NONZERO
+goto ALWAYS
+label NEVER
+label DIFFERENT
+label NONZERO
+label ALWAYS
+push constant 1
+";
+
+    for (x, y) in [(3, 3), (0, 0), (3, 4), (-5, 5)] {
+        assert_optimizes(source, expected, &[(1, 300), (300, x), (301, y)]);
+    }
+}
+
+#[test]
+fn labels_block_rewrites() {
+    let source = "push constant 2\nlabel TARGET\npush constant 3\nadd\npush local 0\nlabel AGAIN\npop local 0";
+    assert_optimizes(source, source, &[(1, 300)]);
+}